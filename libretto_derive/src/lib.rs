@@ -0,0 +1,311 @@
+//! Derive macros for `libretto`'s `IntoLson` and `FromLson` traits. Use them through the `derive`
+//! feature of `libretto`, which is on by default.
+//!
+//! Structs become `Lson::Struct`, tuple structs become `Lson::Array`, and a struct with a single
+//! unnamed field becomes the value of that field. Enums are written the same way `lson::to_value`
+//! writes them: a unit variant is its name, and any other variant is a struct with the name as its
+//! only field. Fields and variants take `#[lson(rename = "name")]` and `#[lson(skip)]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Fields, Generics, Ident, Index, LitStr,
+};
+
+//==================================================================================================
+//          Attributes
+//==================================================================================================
+
+#[derive(Default)]
+struct LsonAttributes {
+    rename: Option<String>,
+    skip: bool,
+}
+
+impl LsonAttributes {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut attributes = LsonAttributes::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("lson")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    attributes.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    attributes.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename = \"...\"` or `skip`"))
+                }
+            })?;
+        }
+        Ok(attributes)
+    }
+
+    fn name(&self, ident: &Ident) -> String {
+        self.rename.clone().unwrap_or_else(|| ident.to_string())
+    }
+}
+
+/// A field that is converted, along with the name it has in Lson. Unnamed fields are named by
+/// their position.
+struct LsonField {
+    member: TokenStream2,
+    binding: Ident,
+    name: String,
+    ty: syn::Type,
+    skip: bool,
+}
+
+fn lson_fields(fields: &Fields) -> syn::Result<Vec<LsonField>> {
+    fields.iter().enumerate().map(|(index, field)| {
+        let attributes = LsonAttributes::parse(&field.attrs)?;
+        let (member, binding, name) = match &field.ident {
+            Some(ident) => (quote!(#ident), ident.clone(), attributes.name(ident)),
+            None => {
+                let index_token = Index::from(index);
+                (quote!(#index_token), format_ident!("field_{}", index), index.to_string())
+            },
+        };
+        Ok(LsonField { member, binding, name, ty: field.ty.clone(), skip: attributes.skip })
+    }).collect()
+}
+
+fn add_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    let params: Vec<Ident> = generics.type_params().map(|param| param.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+    generics
+}
+
+//==================================================================================================
+//          IntoLson
+//==================================================================================================
+
+#[proc_macro_derive(IntoLson, attributes(lson))]
+pub fn derive_into_lson(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_into_lson(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand_into_lson(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let lson = quote!(::libretto::lson);
+    let ident = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(#lson::IntoLson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (body, schema) = match &input.data {
+        Data::Struct(data) => {
+            let fields = lson_fields(&data.fields)?;
+            let members = fields.iter().map(|field| {
+                let member = &field.member;
+                quote!(self.#member)
+            });
+            (into_fields(&data.fields, &fields, members.collect()), fields_schema(&data.fields, &fields))
+        },
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            let mut variants = Vec::new();
+            for variant in &data.variants {
+                let attributes = LsonAttributes::parse(&variant.attrs)?;
+                let variant_ident = &variant.ident;
+                let fields = lson_fields(&variant.fields)?;
+                let bindings: Vec<&Ident> = fields.iter().map(|field| &field.binding).collect();
+                let pattern = match &variant.fields {
+                    Fields::Named(_) => quote!(Self::#variant_ident { #(#bindings),* }),
+                    Fields::Unnamed(_) => quote!(Self::#variant_ident(#(#bindings),*)),
+                    Fields::Unit => quote!(Self::#variant_ident),
+                };
+
+                if attributes.skip {
+                    arms.push(quote!(#[allow(unused_variables)] #pattern => #lson::Lson::None));
+                    continue;
+                }
+
+                let name = attributes.name(variant_ident);
+                if let Fields::Unit = variant.fields {
+                    arms.push(quote!(#pattern => #lson::Lson::String(::std::string::String::from(#name))));
+                    variants.push(quote!((::std::string::String::from(#name), ::std::option::Option::None)));
+                } else {
+                    let inner = into_fields(&variant.fields, &fields, bindings.iter().map(|binding| quote!(#binding)).collect());
+                    let inner_schema = fields_schema(&variant.fields, &fields);
+                    arms.push(quote!(#[allow(unused_variables)] #pattern => #lson::Lson::Struct(
+                        #lson::LsonMap::from([(::std::string::String::from(#name), #inner)])
+                    )));
+                    variants.push(quote!((::std::string::String::from(#name), ::std::option::Option::Some(#inner_schema))));
+                }
+            }
+            (
+                quote!(match self { #(#arms,)* }),
+                quote!(#lson::LsonSchema::Enum(::std::vec![#(#variants),*])),
+            )
+        },
+        Data::Union(data) => return Err(syn::Error::new(data.union_token.span, "IntoLson can't be derived for unions")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #lson::IntoLson for #ident #ty_generics #where_clause {
+            fn into_lson(self) -> #lson::Lson {
+                #body
+            }
+
+            fn lson_schema() -> #lson::LsonSchema {
+                #schema
+            }
+        }
+    })
+}
+
+/// Builds the Lson for a set of fields, given an expression for the value of each field.
+fn into_fields(shape: &Fields, fields: &[LsonField], values: Vec<TokenStream2>) -> TokenStream2 {
+    let lson = quote!(::libretto::lson);
+    let kept: Vec<(&LsonField, &TokenStream2)> = fields.iter().zip(values.iter()).filter(|(field, _)| !field.skip).collect();
+    match shape {
+        Fields::Named(_) => {
+            let names = kept.iter().map(|(field, _)| &field.name);
+            let values = kept.iter().map(|(_, value)| value);
+            quote!({
+                let mut fields = #lson::LsonMap::new();
+                #(fields.insert(::std::string::String::from(#names), #lson::IntoLson::into_lson(#values));)*
+                #lson::Lson::Struct(fields)
+            })
+        },
+        Fields::Unnamed(_) if kept.len() == 1 => {
+            let value = kept[0].1;
+            quote!(#lson::IntoLson::into_lson(#value))
+        },
+        Fields::Unnamed(_) => {
+            let values = kept.iter().map(|(_, value)| value);
+            quote!(#lson::Lson::Array(::std::vec![#(#lson::IntoLson::into_lson(#values)),*]))
+        },
+        Fields::Unit => quote!(#lson::Lson::None),
+    }
+}
+
+fn fields_schema(shape: &Fields, fields: &[LsonField]) -> TokenStream2 {
+    let lson = quote!(::libretto::lson);
+    let kept: Vec<&LsonField> = fields.iter().filter(|field| !field.skip).collect();
+    let types = kept.iter().map(|field| &field.ty);
+    match shape {
+        Fields::Named(_) => {
+            let names = kept.iter().map(|field| &field.name);
+            quote!(#lson::LsonSchema::Struct(::std::vec![
+                #((::std::string::String::from(#names), <#types as #lson::IntoLson>::lson_schema())),*
+            ]))
+        },
+        Fields::Unnamed(_) if kept.len() == 1 => {
+            let ty = &kept[0].ty;
+            quote!(<#ty as #lson::IntoLson>::lson_schema())
+        },
+        Fields::Unnamed(_) => quote!(#lson::LsonSchema::Tuple(::std::vec![#(<#types as #lson::IntoLson>::lson_schema()),*])),
+        Fields::Unit => quote!(#lson::LsonSchema::Value(#lson::LsonType::None)),
+    }
+}
+
+//==================================================================================================
+//          FromLson
+//==================================================================================================
+
+#[proc_macro_derive(FromLson, attributes(lson))]
+pub fn derive_from_lson(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_lson(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand_from_lson(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let lson = quote!(::libretto::lson);
+    let ident = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(#lson::FromLson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = lson_fields(&data.fields)?;
+            from_fields(quote!(Self), &data.fields, &fields, quote!(value))
+        },
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let attributes = LsonAttributes::parse(&variant.attrs)?;
+                if attributes.skip {
+                    continue;
+                }
+                let variant_ident = &variant.ident;
+                let name = attributes.name(variant_ident);
+                let fields = lson_fields(&variant.fields)?;
+                let construct = from_fields(quote!(Self::#variant_ident), &variant.fields, &fields, quote!(inner));
+                arms.push(quote!(#name => #construct));
+            }
+            quote!({
+                let (variant, inner) = #lson::take_variant(value)?;
+                match variant.as_str() {
+                    #(#arms,)*
+                    _ => ::std::result::Result::Err(#lson::LsonError::UnknownVariant(variant)),
+                }
+            })
+        },
+        Data::Union(data) => return Err(syn::Error::new(data.union_token.span, "FromLson can't be derived for unions")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #lson::FromLson for #ident #ty_generics #where_clause {
+            fn from_lson(value: #lson::Lson) -> ::std::result::Result<Self, #lson::LsonError> {
+                #body
+            }
+        }
+    })
+}
+
+/// Builds a struct or variant at `path` out of the Lson in `value`. Skipped fields get their default.
+fn from_fields(path: TokenStream2, shape: &Fields, fields: &[LsonField], value: TokenStream2) -> TokenStream2 {
+    let lson = quote!(::libretto::lson);
+    let kept = fields.iter().filter(|field| !field.skip).count();
+    let default = quote!(::std::default::Default::default());
+    match shape {
+        Fields::Named(_) => {
+            let inits = fields.iter().map(|field| {
+                let member = &field.member;
+                let name = &field.name;
+                if field.skip {
+                    quote!(#member: #default)
+                } else {
+                    quote!(#member: #lson::take_field(&mut fields, #name)?)
+                }
+            });
+            quote!({
+                #[allow(unused_mut)]
+                let mut fields = #lson::take_struct(#value)?;
+                ::std::result::Result::Ok(#path { #(#inits),* })
+            })
+        },
+        Fields::Unnamed(_) if kept == 1 => {
+            let inits = fields.iter().map(|field| {
+                if field.skip { default.clone() } else { quote!(#lson::FromLson::from_lson(#value)?) }
+            });
+            quote!(::std::result::Result::Ok(#path(#(#inits),*)))
+        },
+        Fields::Unnamed(_) => {
+            let inits = fields.iter().map(|field| {
+                if field.skip {
+                    default.clone()
+                } else {
+                    let name = &field.name;
+                    quote!(#lson::FromLson::from_lson(values.next().unwrap())
+                        .map_err(|error| #lson::LsonError::InField(::std::string::String::from(#name), ::std::boxed::Box::new(error)))?)
+                }
+            });
+            quote!({
+                #[allow(unused_mut)]
+                let mut values = #lson::take_tuple(#value, #kept)?;
+                ::std::result::Result::Ok(#path(#(#inits),*))
+            })
+        },
+        Fields::Unit => quote!({
+            let _ = #value;
+            ::std::result::Result::Ok(#path)
+        }),
+    }
+}
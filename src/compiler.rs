@@ -1,18 +1,54 @@
 use std::collections::HashMap;
 
+use logos::Logos;
+
+use crate::diagnostic::{Diagnostic, Diagnostics};
+use crate::lexer::{LibrettoToken, LibrettoTokenQueue};
+use crate::parse::{LibrettoParsable, Script};
 use crate::scope::LibrettoScope;
-use crate::lson::{Lson, LsonType};
+use crate::script::LibrettoScript;
+use crate::lson::{Lson, LsonSchema, LsonType};
+use crate::span::Span;
+use crate::speaker::LibrettoSpeakerRegistry;
+
+/// Compiles a whole script with the default lints and no speaker registry. Use
+/// [`LibrettoCompiletime::compile`] to compile against host variables, speakers or lint levels.
+pub fn compile(source : &str) -> Result<LibrettoScript, Diagnostics> {
+    LibrettoCompiletime::default().compile(source)
+}
 
 pub struct LibrettoCompiletime {
     current_scope: LibrettoScope<LsonType>,
-    errors : Vec<LibrettoCompileError>
+    speakers: Option<LibrettoSpeakerRegistry>,
+    errors : Vec<LibrettoCompileError>,
+    warnings : Vec<LibrettoCompileWarning>,
+    lints : LibrettoLintConfig,
+    declarations : Vec<VariableDeclaration>,
+    schemas : HashMap<String, LsonSchema>,
+}
+
+/// A variable declared by the script, kept until its scope is popped so that it can be reported if
+/// nothing ever reads it. A variable declared without a type or value gets its type from the first
+/// assignment to it, and `typed_at` remembers where that happened.
+struct VariableDeclaration {
+    ident: String,
+    span: Span,
+    typed_at: Option<Span>,
+    depth: u32,
+    request: bool,
+    used: bool,
 }
 
 impl Default for LibrettoCompiletime {
     fn default() -> Self {
         LibrettoCompiletime {
             current_scope : LibrettoScope {data : HashMap::new(), parrent: None},
-            errors : Vec::new()
+            speakers : None,
+            errors : Vec::new(),
+            warnings : Vec::new(),
+            lints : LibrettoLintConfig::default(),
+            declarations : Vec::new(),
+            schemas : HashMap::new(),
         }
     }
 }
@@ -22,13 +58,127 @@ impl LibrettoCompiletime {
     pub fn with_data(data : impl Into<HashMap<String, LsonType>>) -> Self {
         LibrettoCompiletime {
             current_scope: LibrettoScope { data: data.into(), parrent: None },
-            errors : Vec::new()
+            ..Default::default()
         }
     }
 
+    /// Gives the script a host variable along with the shape of its value, usually from
+    /// `T::lson_schema()`, so that reading a field of it like `player.name` has a known type.
+    pub fn with_schema(mut self, ident : &str, schema : LsonSchema) -> Self {
+        self.current_scope.data.insert(ident.to_string(), schema.lson_type());
+        self.schemas.insert(ident.to_string(), schema);
+        self
+    }
+
+    /// Lexes, parses and validates a whole script. Any error, including a warning whose lint is
+    /// denied, fails the compile with every diagnostic that was found. Otherwise the script is given
+    /// back along with its warnings.
+    pub fn compile(mut self, source : &str) -> Result<LibrettoScript, Diagnostics> {
+        let mut queue = LibrettoTokenQueue::from(LibrettoToken::lexer(source));
+        let script = Script::parse(&mut queue, &mut self);
+        if let Some(script) = &script {
+            script.validate(&mut self);
+        }
+
+        let diagnostics = Diagnostics::from(self.diagnostics());
+        match script {
+            Some(script) if self.error_count() == 0 => Ok(LibrettoScript::new(script, diagnostics)),
+            _ => Err(diagnostics),
+        }
+    }
+
+    pub fn set_lints(&mut self, lints : LibrettoLintConfig) {
+        self.lints = lints;
+    }
+
+    pub fn lints_mut(&mut self) -> &mut LibrettoLintConfig {
+        &mut self.lints
+    }
+
+    pub fn set_speakers(&mut self, speakers : LibrettoSpeakerRegistry) {
+        self.speakers = Some(speakers);
+    }
+
+    /// Checks a speaker against the speaker registry. Without a registry, every speaker is known.
+    pub fn is_speaker_known(&self, id : &str) -> bool {
+        self.speakers.as_ref().is_none_or(|speakers| speakers.contains(id))
+    }
+
     pub fn get_variable_type(&self, key : &str) -> LsonType {
         self.current_scope.get_data(key)
     }
+
+    /// Gets the type of a variable that is being read, and marks its declaration as used. Reading a
+    /// variable whose type is still waiting on its first assignment is an error.
+    pub fn use_variable(&mut self, ident : &str, span : Span) -> LsonType {
+        let mut pending = false;
+        if let Some(declaration) = self.declarations.iter_mut().rev().find(|declaration| declaration.ident == ident) {
+            declaration.used = true;
+            pending = declaration.typed_at.is_none();
+        }
+        if pending {
+            self.push_error(LibrettoCompileErrorKind::VariableTypeNotInferred(ident.to_string()), span);
+        }
+        self.get_variable_type(ident)
+    }
+
+    /// Gets the type of a field of a variable that is being read, like `player.name`. The type comes
+    /// from the schema the host gave for the variable. Without one, the fields of a struct are only
+    /// known at runtime, so they have the type `None`.
+    pub fn use_field(&mut self, ident : &str, path : &str, span : Span) -> LsonType {
+        let root = self.use_variable(ident, span);
+        let declared = self.declarations.iter().any(|declaration| declaration.ident == ident);
+        let field = match self.schemas.get(ident).filter(|_| !declared) {
+            Some(schema) => schema.path(path).map(LsonSchema::lson_type),
+            None if matches!(root, LsonType::Struct | LsonType::None) => Some(LsonType::None),
+            None => None,
+        };
+        field.unwrap_or_else(|| {
+            self.push_error(LibrettoCompileErrorKind::FieldNotDefined(format!("{ident}.{path}"), root.to_string()), span);
+            LsonType::None
+        })
+    }
+
+    /// Declares a variable in the current scope, warning if it shadows one that is already visible.
+    /// Requests are declared the same way, but are reported with their own lint when unused. A type
+    /// of `None` leaves the type to be inferred by [`LibrettoCompiletime::infer_variable_type`].
+    pub fn declare_variable(&mut self, ident : &str, value : LsonType, span : Span, request : bool) {
+        if self.variable_depth(ident) >= 0 {
+            self.push_warning(LibrettoCompileWarningKind::ShadowedVariable(ident.to_string()), span);
+        }
+        self.insert_variable_type(ident, value);
+        self.declarations.push(VariableDeclaration {
+            ident: ident.to_string(),
+            span,
+            typed_at: (value != LsonType::None).then_some(span),
+            depth: self.current_scope.depth(),
+            request,
+            used: false,
+        });
+    }
+
+    /// Whether a declared variable is still waiting for an assignment to give it a type.
+    pub fn is_variable_pending(&self, ident : &str) -> bool {
+        self.declaration(ident).is_some_and(|declaration| declaration.typed_at.is_none())
+    }
+
+    /// Gives a pending variable the type of its first assignment, in the scope it was declared in.
+    pub fn infer_variable_type(&mut self, ident : &str, value : LsonType, span : Span) {
+        self.current_scope.replace_data(ident, value);
+        if let Some(declaration) = self.declarations.iter_mut().rev().find(|declaration| declaration.ident == ident) {
+            declaration.typed_at = Some(span);
+        }
+    }
+
+    /// Where the type of a variable was decided: its declaration, or the assignment it was inferred
+    /// from. Variables that came from outside of the script have no origin.
+    pub fn variable_origin(&self, ident : &str) -> Option<Span> {
+        self.declaration(ident).and_then(|declaration| declaration.typed_at)
+    }
+
+    fn declaration(&self, ident : &str) -> Option<&VariableDeclaration> {
+        self.declarations.iter().rev().find(|declaration| declaration.ident == ident)
+    }
     
     pub fn push_scope(&mut self, data : impl Into<HashMap<String, LsonType>>) {
         let next_scope = LibrettoScope::new(data);
@@ -37,6 +187,7 @@ impl LibrettoCompiletime {
     }
 
     pub fn pop_scope(&mut self) {
+        self.report_declarations(self.current_scope.depth());
         if let Some(parrent) = std::mem::replace(&mut self.current_scope.parrent, None) {
             self.current_scope = *parrent;
         }
@@ -46,12 +197,68 @@ impl LibrettoCompiletime {
         self.current_scope.data.insert(ident.to_string(), value);
     }
 
+    /// Reports every declaration at or below the given scope depth that never got a type or was never
+    /// read, and forgets them. Passing a depth of 1 reports what is left in the root scope once a
+    /// script is validated.
+    pub fn report_declarations(&mut self, depth : u32) {
+        let split = self.declarations.iter().position(|declaration| declaration.depth >= depth).unwrap_or(self.declarations.len());
+        for declaration in self.declarations.split_off(split) {
+            if declaration.typed_at.is_none() {
+                // A pending variable that was read has already been reported where it was read.
+                if !declaration.used {
+                    self.push_error(LibrettoCompileErrorKind::TypeNotExplicit(declaration.ident), declaration.span);
+                }
+                continue;
+            }
+            if declaration.used || declaration.ident.starts_with('_') {
+                continue;
+            }
+            let kind = if declaration.request {
+                LibrettoCompileWarningKind::UnusedRequest(declaration.ident)
+            } else {
+                LibrettoCompileWarningKind::UnusedVariable(declaration.ident)
+            };
+            self.push_warning(kind, declaration.span);
+        }
+    }
+
+    /// The number of errors, including warnings whose lint is denied.
     pub fn error_count(&self) -> usize {
-        self.errors.len()
+        self.errors.len() + self.warnings.iter().filter(|warning| warning.level == LintLevel::Deny).count()
+    }
+
+    pub fn errors(&self) -> &Vec<LibrettoCompileError> {
+        &self.errors
+    }
+
+    pub fn warnings(&self) -> &Vec<LibrettoCompileWarning> {
+        &self.warnings
+    }
+
+    /// The errors and warnings found so far, as diagnostics that can be rendered against the source.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = self.errors.iter().map(Diagnostic::from)
+            .chain(self.warnings.iter().map(Diagnostic::from))
+            .collect();
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+        diagnostics
+    }
+
+    pub fn push_error(&mut self, kind: LibrettoCompileErrorKind, span: Span) {
+        self.errors.push(kind.at(span))
+    }
+
+    /// Pushes an error that has already been built, for errors that carry more than a span.
+    pub fn push(&mut self, error: LibrettoCompileError) {
+        self.errors.push(error)
     }
 
-    pub fn push_error(&mut self, value: LibrettoCompileError) {
-        self.errors.push(value)
+    /// Pushes a warning at the level its lint is configured to. Allowed lints are dropped.
+    pub fn push_warning(&mut self, kind: LibrettoCompileWarningKind, span: Span) {
+        let level = self.lints.level(kind.lint());
+        if level != LintLevel::Allow {
+            self.warnings.push(LibrettoCompileWarning { kind, span, level });
+        }
     }
 
     pub fn has_variable_type(&self, ident : &str) -> bool {
@@ -63,8 +270,86 @@ impl LibrettoCompiletime {
     }
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum LibrettoCompileError {
+/// An error found while compiling a script, along with where in the script it was found. Errors
+/// about a conflict can also point at the other side of it, like where a variable got its type.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{kind}")]
+pub struct LibrettoCompileError {
+    pub kind: LibrettoCompileErrorKind,
+    pub span: Span,
+    pub origin: Option<Span>,
+}
+
+impl LibrettoCompileError {
+    pub fn with_origin(mut self, origin: Option<Span>) -> Self {
+        self.origin = origin;
+        self
+    }
+}
+
+impl LibrettoCompileErrorKind {
+    pub fn at(self, span: Span) -> LibrettoCompileError {
+        LibrettoCompileError { kind: self, span, origin: None }
+    }
+
+    /// A short code that names the kind of error, and stays the same when its message is reworded.
+    pub fn code(&self) -> &'static str {
+        use LibrettoCompileErrorKind::*;
+        match self {
+            NullValueError => "E0001",
+            OperationNotSupportedError(..) => "E0002",
+            InvalidOperationError(..) => "E0003",
+            ParseCheckNotThoroughError(..) => "E0004",
+            ExprDefaultTypeMissmatch(..) => "E0005",
+            AssignmentWithInvalidType(..) => "E0006",
+            AssignmentWithUndeclaredVariable(..) => "E0007",
+            TypeNotExplicit(..) => "E0008",
+            AssignmentStatementTypeMismatch(..) => "E0009",
+            ConditionNotBool(..) => "E0010",
+            GuardReasonNotString(..) => "E0011",
+            SpeakerNotString(..) => "E0012",
+            SpeakerNotDefined(..) => "E0013",
+            TargetNotDefined(..) => "E0014",
+            TargetAmbiguous(..) => "E0015",
+            WaitNotNumber(..) => "E0016",
+            RecursiveCall(..) => "E0017",
+            UnexpectedToken(..) => "E0018",
+            LineTextRepeated => "E0019",
+            LineDivertRepeated => "E0020",
+            VariantOutOfOrder => "E0021",
+            UnknownAttribute(..) => "E0022",
+            VariableTypeNotInferred(..) => "E0023",
+            LiteralOutOfRange(..) => "E0024",
+            FieldNotDefined(..) => "E0025",
+        }
+    }
+
+    /// A hint at how to fix the error, written for the person writing the script.
+    pub fn help(&self) -> Option<String> {
+        use LibrettoCompileErrorKind::*;
+        match self {
+            ExprDefaultTypeMissmatch(expected, _) => Some(format!("The value after `?` is used when the expression has no value, so it should be a {expected} as well.")),
+            AssignmentWithUndeclaredVariable(ident) => Some(format!("Declare the variable first, like `let {ident} = ...;`.")),
+            AssignmentWithInvalidType(ident, expected, _) => Some(format!("`{ident}` is a {expected}. Use a new variable for values of another type.")),
+            TypeNotExplicit(ident) => Some(format!("Give the variable a type or a value, like `let {ident} : int;` or `let {ident} = 0;`, or assign to it after declaring it.")),
+            VariableTypeNotInferred(ident) => Some(format!("Assign to `{ident}` before reading it, or give it a type, like `let {ident} : int;`.")),
+            ConditionNotBool(_) => Some("A condition has to be true or false. Compare the value with something, like `gold > 0`.".to_string()),
+            SpeakerNotDefined(_) => Some("Check the spelling of the speaker, or add them to the speaker registry.".to_string()),
+            TargetNotDefined(_) => Some("Targets are written as `dialog` or `dialog.label`, and labels as `@label`.".to_string()),
+            TargetAmbiguous(_) => Some("A dialog and a label in this dialog share the name. Rename one of them.".to_string()),
+            WaitNotNumber(_) => Some("Give the number of seconds to wait, like `wait 1.5`.".to_string()),
+            RecursiveCall(_) => Some("Use a divert instead of a call if the dialog should not come back.".to_string()),
+            LineTextRepeated => Some("Write the rest of the text as its own line, starting with `|`.".to_string()),
+            VariantOutOfOrder => Some("A line starts its variants with `<if ...>`, and `<else>` comes last.".to_string()),
+            LiteralOutOfRange(LsonType::Int) => Some(format!("Ints run from {} to {}. Use a float for numbers outside of that.", i64::MIN, i64::MAX)),
+            FieldNotDefined(..) => Some("Fields can only be read from structs. Check the spelling against the type the host gave.".to_string()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum LibrettoCompileErrorKind {
     #[error("Values are not allowed to be set to null.")]
     NullValueError,
 
@@ -80,8 +365,8 @@ pub enum LibrettoCompileError {
     #[error("When parsing an expression with type {0}, there was a default supplied with type {1}. These types must be the same.")]
     ExprDefaultTypeMissmatch(String, String),
 
-    #[error("Cannot assign value to variable {0} because the variable is not the same type.")]
-    AssignmentWithInvalidType(String),
+    #[error("Cannot assign a value of type {2} to variable '{0}', which is of type {1}.")]
+    AssignmentWithInvalidType(String, String, String),
     
     #[error("Cannot assign value to undeclared variable '{0}'.")]
     AssignmentWithUndeclaredVariable(String),
@@ -91,4 +376,227 @@ pub enum LibrettoCompileError {
     
     #[error("When parsing the expression, the declared type ({0}) did not match the derived type ({1}).")]
     AssignmentStatementTypeMismatch(String, String),
+
+    #[error("A condition must be of type bool, but it is of type {0}.")]
+    ConditionNotBool(String),
+
+    #[error("The reason given by a response guard must be of type string, but it is of type {0}.")]
+    GuardReasonNotString(String),
+
+    #[error("A speaker must be of type string, but it is of type {0}.")]
+    SpeakerNotString(String),
+
+    #[error("The speaker '{0}' is not in the speaker registry.")]
+    SpeakerNotDefined(String),
+
+    #[error("There is no dialog or label named '{0}'.")]
+    TargetNotDefined(String),
+
+    #[error("The target '{0}' could lead to more than one dialog or label.")]
+    TargetAmbiguous(String),
+
+    #[error("A wait must be given a number of seconds of type int or float, but it is of type {0}.")]
+    WaitNotNumber(String),
+
+    #[error("The call to '{0}' can lead back to itself before it returns, so the calls could go on without end.")]
+    RecursiveCall(String),
+
+    #[error("Expected {0}, but found {1}.")]
+    UnexpectedToken(String, String),
+
+    #[error("A line can only have one quote.")]
+    LineTextRepeated,
+
+    #[error("A line can only have one divert.")]
+    LineDivertRepeated,
+
+    #[error("The variants of a line must start with an if, and nothing can follow an else.")]
+    VariantOutOfOrder,
+
+    #[error("'{0}' is not a known attribute. Attributes are written like `#allow(unused_variable)`.")]
+    UnknownAttribute(String),
+
+    #[error("The variable '{0}' is read before anything is assigned to it, so its type is not known yet.")]
+    VariableTypeNotInferred(String),
+
+    #[error("The number is too large to be stored as a {}.", .0.to_string())]
+    LiteralOutOfRange(LsonType),
+
+    #[error("There is no field '{0}' in a value of type {1}.")]
+    FieldNotDefined(String, String),
+}
+
+//==================================================================================================
+//          Lints
+//==================================================================================================
+
+/// A kind of warning that can be allowed, warned about or denied on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LibrettoLint {
+    UnusedVariable,
+    ShadowedVariable,
+    UnreachableDialog,
+    ResponseLeadsNowhere,
+    ConstantCondition,
+    UnusedRequest,
+}
+
+impl LibrettoLint {
+    pub const ALL: [LibrettoLint; 6] = [
+        LibrettoLint::UnusedVariable,
+        LibrettoLint::ShadowedVariable,
+        LibrettoLint::UnreachableDialog,
+        LibrettoLint::ResponseLeadsNowhere,
+        LibrettoLint::ConstantCondition,
+        LibrettoLint::UnusedRequest,
+    ];
+
+    /// The name used for the lint in attributes, like `#deny(unused_variable)`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LibrettoLint::UnusedVariable => "unused_variable",
+            LibrettoLint::ShadowedVariable => "shadowed_variable",
+            LibrettoLint::UnreachableDialog => "unreachable_dialog",
+            LibrettoLint::ResponseLeadsNowhere => "response_leads_nowhere",
+            LibrettoLint::ConstantCondition => "constant_condition",
+            LibrettoLint::UnusedRequest => "unused_request",
+        }
+    }
+
+    pub fn from_name(name : &str) -> Option<Self> {
+        LibrettoLint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            LibrettoLint::UnusedVariable => "W0001",
+            LibrettoLint::ShadowedVariable => "W0002",
+            LibrettoLint::UnreachableDialog => "W0003",
+            LibrettoLint::ResponseLeadsNowhere => "W0004",
+            LibrettoLint::ConstantCondition => "W0005",
+            LibrettoLint::UnusedRequest => "W0006",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LintLevel {
+    Allow,
+    #[default]
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    pub fn from_name(name : &str) -> Option<Self> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// The level of every lint. Lints without a level of their own use the default level, which warns
+/// unless it is changed, so `LibrettoLintConfig::default().with_default(LintLevel::Deny)` makes every
+/// warning an error.
+#[derive(Debug, Clone, Default)]
+pub struct LibrettoLintConfig {
+    levels: HashMap<LibrettoLint, LintLevel>,
+    default: LintLevel,
+}
+
+impl LibrettoLintConfig {
+    pub fn with_default(mut self, level : LintLevel) -> Self {
+        self.default = level;
+        self
+    }
+
+    pub fn with(mut self, lint : LibrettoLint, level : LintLevel) -> Self {
+        self.set(lint, level);
+        self
+    }
+
+    pub fn allow(self, lint : LibrettoLint) -> Self {
+        self.with(lint, LintLevel::Allow)
+    }
+
+    pub fn deny(self, lint : LibrettoLint) -> Self {
+        self.with(lint, LintLevel::Deny)
+    }
+
+    pub fn set(&mut self, lint : LibrettoLint, level : LintLevel) {
+        self.levels.insert(lint, level);
+    }
+
+    pub fn level(&self, lint : LibrettoLint) -> LintLevel {
+        self.levels.get(&lint).copied().unwrap_or(self.default)
+    }
+
+    /// Applies an attribute like `allow(unused_variable)`, as it is written after the `#` of a tag at
+    /// the top of a script. Returns false if the attribute is not a lint level.
+    pub fn apply_attribute(&mut self, attribute : &str) -> bool {
+        let parsed = attribute.strip_suffix(')')
+            .and_then(|attribute| attribute.split_once('('))
+            .and_then(|(level, lint)| Some((LintLevel::from_name(level)?, LibrettoLint::from_name(lint.trim())?)));
+        match parsed {
+            Some((level, lint)) => {
+                self.set(lint, level);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A warning found while compiling a script. A warning whose lint is denied counts as an error.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{kind}")]
+pub struct LibrettoCompileWarning {
+    pub kind: LibrettoCompileWarningKind,
+    pub span: Span,
+    pub level: LintLevel,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum LibrettoCompileWarningKind {
+    #[error("The variable '{0}' is never used.")]
+    UnusedVariable(String),
+
+    #[error("The variable '{0}' shadows another variable with the same name.")]
+    ShadowedVariable(String),
+
+    #[error("The dialog '{0}' can never be reached from '{1}'.")]
+    UnreachableDialog(String, String),
+
+    #[error("The response \"{0}\" leads to a dialog with no lines.")]
+    ResponseLeadsNowhere(String),
+
+    #[error("This condition doesn't depend on any variable, so it always has the same result.")]
+    ConstantCondition,
+
+    #[error("The request for '{0}' is never used.")]
+    UnusedRequest(String),
+}
+
+impl LibrettoCompileWarningKind {
+    pub fn lint(&self) -> LibrettoLint {
+        match self {
+            LibrettoCompileWarningKind::UnusedVariable(_) => LibrettoLint::UnusedVariable,
+            LibrettoCompileWarningKind::ShadowedVariable(_) => LibrettoLint::ShadowedVariable,
+            LibrettoCompileWarningKind::UnreachableDialog(..) => LibrettoLint::UnreachableDialog,
+            LibrettoCompileWarningKind::ResponseLeadsNowhere(_) => LibrettoLint::ResponseLeadsNowhere,
+            LibrettoCompileWarningKind::ConstantCondition => LibrettoLint::ConstantCondition,
+            LibrettoCompileWarningKind::UnusedRequest(_) => LibrettoLint::UnusedRequest,
+        }
+    }
+
+    pub fn help(&self) -> Option<String> {
+        match self {
+            LibrettoCompileWarningKind::UnusedVariable(ident) => Some(format!("If this is on purpose, name the variable `_{ident}`.")),
+            LibrettoCompileWarningKind::UnreachableDialog(..) => Some("Add a response or divert that leads to it, or allow this lint if the host starts it directly.".to_string()),
+            _ => None,
+        }
+    }
 }
\ No newline at end of file
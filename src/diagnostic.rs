@@ -0,0 +1,311 @@
+use std::fmt::{Display, Write};
+
+use crate::compiler::{LibrettoCompileError, LibrettoCompileWarning, LintLevel};
+use crate::span::Span;
+
+//==================================================================================================
+//          Diagnostic
+//==================================================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A message about a script, tied to where in the script it applies. The code is stable between
+/// versions, so writers can look it up, while the message and help may be reworded. Notes point at
+/// other places in the script that explain the message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+    pub help: Vec<String>,
+    pub notes: Vec<(String, Span)>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Diagnostic { severity, code, message: message.into(), span, help: Vec::new(), notes: Vec::new() }
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>, span: Span) -> Self {
+        self.notes.push((note.into(), span));
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl From<&LibrettoCompileError> for Diagnostic {
+    fn from(error: &LibrettoCompileError) -> Self {
+        let mut diagnostic = Diagnostic::new(Severity::Error, error.kind.code(), error.kind.to_string(), error.span);
+        if let Some(origin) = error.origin {
+            diagnostic = diagnostic.with_note("The type was decided here.", origin);
+        }
+        match error.kind.help() {
+            Some(help) => diagnostic.with_help(help),
+            None => diagnostic,
+        }
+    }
+}
+
+impl From<&LibrettoCompileWarning> for Diagnostic {
+    fn from(warning: &LibrettoCompileWarning) -> Self {
+        let lint = warning.kind.lint();
+        let severity = if warning.level == LintLevel::Deny { Severity::Error } else { Severity::Warning };
+        let mut diagnostic = Diagnostic::new(severity, lint.code(), warning.kind.to_string(), warning.span);
+        if let Some(help) = warning.kind.help() {
+            diagnostic = diagnostic.with_help(help);
+        }
+        diagnostic.with_help(format!("`#allow({})` at the top of the script turns this off.", lint.name()))
+    }
+}
+
+impl From<LibrettoCompileError> for Diagnostic {
+    fn from(error: LibrettoCompileError) -> Self {
+        Diagnostic::from(&error)
+    }
+}
+
+//==================================================================================================
+//          Diagnostics
+//==================================================================================================
+
+/// Every diagnostic found while compiling a script, in the order they appear in the source. This is
+/// what a failed compile gives back, and a compiled script keeps the warnings it was built with.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|diagnostic| diagnostic.is_error())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|diagnostic| !diagnostic.is_error())
+    }
+
+    /// Renders every diagnostic against the source the script was compiled from.
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        DiagnosticRenderer::new(file_name, source).render_all(self)
+    }
+}
+
+impl From<Vec<Diagnostic>> for Diagnostics {
+    fn from(diagnostics: Vec<Diagnostic>) -> Self {
+        Diagnostics { diagnostics }
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.iter()
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}[{}] at {}: {}", diagnostic.severity, diagnostic.code, diagnostic.span, diagnostic.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+//==================================================================================================
+//          Diagnostic Renderer
+//==================================================================================================
+
+/// Renders diagnostics against the source they were found in, showing the file, the position, the
+/// offending line with carets under the span, and any help notes:
+///
+/// ```text
+/// error[E0010]: A condition must be of type bool, but it is of type int.
+///   --> intro.lib:3:12
+///    |
+///  3 |     | <if gold> "You have gold."
+///    |            ^^^^
+///    = help: Compare the value with something, like `gold > 0`.
+/// ```
+pub struct DiagnosticRenderer<'a> {
+    file_name: &'a str,
+    source: &'a str,
+}
+
+impl<'a> DiagnosticRenderer<'a> {
+    pub fn new(file_name: &'a str, source: &'a str) -> Self {
+        DiagnosticRenderer { file_name, source }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let widest_line = diagnostic.notes.iter().map(|(_, span)| span.line).fold(diagnostic.span.line, usize::max);
+        let gutter = " ".repeat(widest_line.to_string().len());
+
+        let mut out = String::new();
+        let _ = writeln!(out, "{}[{}]: {}", diagnostic.severity, diagnostic.code, diagnostic.message);
+        self.render_snippet(&mut out, &gutter, diagnostic.span);
+
+        for (note, span) in &diagnostic.notes {
+            let _ = writeln!(out, "{gutter} = note: {note}");
+            self.render_snippet(&mut out, &gutter, *span);
+        }
+
+        for help in &diagnostic.help {
+            let _ = writeln!(out, "{gutter} = help: {help}");
+        }
+
+        out
+    }
+
+    /// Renders every diagnostic, separated by blank lines.
+    pub fn render_all<'d>(&self, diagnostics: impl IntoIterator<Item = &'d Diagnostic>) -> String {
+        diagnostics.into_iter().map(|diagnostic| self.render(diagnostic)).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Writes the position of a span, and the line it is on with carets under it.
+    fn render_snippet(&self, out: &mut String, gutter: &str, span: Span) {
+        let _ = writeln!(out, "{gutter}--> {}:{}", self.file_name, span);
+
+        if let Some((line_start, line)) = self.source_line(span) {
+            let line_number = format!("{:>width$}", span.line, width = gutter.len());
+            let _ = writeln!(out, "{gutter} |");
+            let _ = writeln!(out, "{line_number} | {line}");
+
+            // Keep tabs under tabs so the carets line up however the line is displayed.
+            let before = &self.source[line_start..span.start.clamp(line_start, line_start + line.len())];
+            let padding: String = before.chars().map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+            let underlined = &self.source[span.start.min(line_start + line.len())..span.end.min(line_start + line.len())];
+            let carets = "^".repeat(underlined.chars().count().max(1));
+            let _ = writeln!(out, "{gutter} | {padding}{carets}");
+        }
+    }
+
+    /// The line a span starts on, without its line ending, and the byte offset that line starts at.
+    fn source_line(&self, span: Span) -> Option<(usize, &'a str)> {
+        if span.start > self.source.len() {
+            return None;
+        }
+        let start = self.source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let end = self.source[span.start..].find('\n').map_or(self.source.len(), |i| span.start + i);
+        Some((start, self.source[start..end].trim_end_matches('\r')))
+    }
+}
+
+//==================================================================================================
+//          Diagnostic Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::LibrettoCompileErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn render_diagnostic() {
+        let source = "start {\n    | <if gold> \"You have gold.\"\n}";
+        let span = Span::locate(source, 18, 22);
+        let diagnostic = Diagnostic::from(LibrettoCompileErrorKind::ConditionNotBool("int".to_string()).at(span));
+
+        let rendered = DiagnosticRenderer::new("intro.lib", source).render(&diagnostic);
+        let expected = [
+            "error[E0010]: A condition must be of type bool, but it is of type int.",
+            " --> intro.lib:2:11",
+            "  |",
+            "2 |     | <if gold> \"You have gold.\"",
+            "  |           ^^^^",
+            "  = help: A condition has to be true or false. Compare the value with something, like `gold > 0`.",
+            "",
+        ].join("\n");
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn render_diagnostic_with_note() {
+        let source = "<let gold; gold = 2;>\nstart {\n    | <gold = \"none\">\n}";
+        let kind = LibrettoCompileErrorKind::AssignmentWithInvalidType("gold".to_string(), "int".to_string(), "string".to_string());
+        let error = kind.at(Span::locate(source, 37, 50)).with_origin(Some(Span::locate(source, 11, 19)));
+
+        let rendered = DiagnosticRenderer::new("gold.lib", source).render(&Diagnostic::from(error));
+        let expected = [
+            "error[E0006]: Cannot assign a value of type string to variable 'gold', which is of type int.",
+            " --> gold.lib:3:8",
+            "  |",
+            "3 |     | <gold = \"none\">",
+            "  |        ^^^^^^^^^^^^^",
+            "  = note: The type was decided here.",
+            " --> gold.lib:1:12",
+            "  |",
+            "1 | <let gold; gold = 2;>",
+            "  |            ^^^^^^^^",
+            "  = help: `gold` is a int. Use a new variable for values of another type.",
+            "",
+        ].join("\n");
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn render_diagnostic_with_tabs() {
+        let source = "start {\n\t| <foo = 2.0>\n}";
+        let span = Span::locate(source, 12, 21);
+        let diagnostic = Diagnostic::new(Severity::Warning, "W0001", "Something odd.", span);
+
+        let rendered = DiagnosticRenderer::new("tabs.lib", source).render(&diagnostic);
+        assert!(rendered.starts_with("warning[W0001]: Something odd.\n"));
+        assert!(rendered.contains("2 | \t| <foo = 2.0>\n  | \t   ^^^^^^^^^\n"));
+    }
+
+    #[test]
+    fn render_diagnostic_past_line_end() {
+        let source = "start {\n    | \"Hi\" -- nowhere\n";
+        let span = Span::locate(source, 30, 30);
+        let diagnostic = Diagnostic::new(Severity::Error, "E0000", "Unexpected end.", span);
+
+        let rendered = DiagnosticRenderer::new("end.lib", source).render(&diagnostic);
+        assert!(rendered.contains("3 | \n  | ^\n"));
+    }
+}
@@ -1,5 +1,6 @@
-use logos::{Lexer, Logos};
+use logos::{Lexer, Logos, Span as Range};
 use crate::lson::LsonType;
+use crate::span::{SourceCursor, Span};
 use peekmore::{PeekMore, PeekMoreIterator};
 use std::{fmt::Debug, marker::PhantomData};
 use strum::EnumDiscriminants;
@@ -8,32 +9,71 @@ use strum::EnumDiscriminants;
 //          Libretto Token Queue
 //==================================================================================================
 
+/// A lexer that gives the range of each token along with it. Unlike `logos::SpannedIter`, this can
+/// be cloned, which the queue needs to look ahead.
+#[derive(Clone)]
+pub struct SpannedLexer<'a, T: Logos<'a>>(Lexer<'a, T>)
+where
+    T::Extras: Clone;
+
+impl<'a, T: Logos<'a>> Iterator for SpannedLexer<'a, T>
+where
+    T::Extras: Clone,
+{
+    type Item = (T, Range);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.0.next()?;
+        Some((token, self.0.span()))
+    }
+}
+
+/// A queue of tokens that can be looked ahead into. Every token keeps the span it was lexed from, so
+/// that parsed nodes and errors can point back into the source. Queues that are lexed from inside of
+/// another token, like the contents of a logic block, keep the whole source and the offset of their
+/// slice within it, so their spans line up with the source they came from.
 #[derive(Clone)]
 pub struct LibrettoTokenQueue<'a, T>
 where
-    T: Logos<'a> + PartialEq + Clone + Ordinal + Debug,
+    T: Logos<'a, Source = str> + PartialEq + Clone + Ordinal + Debug,
     T::Extras: Clone,
 {
-    iterator: PeekMoreIterator<Lexer<'a, T>>,
+    iterator: PeekMoreIterator<SpannedLexer<'a, T>>,
     cursor: usize,
+    source: &'a str,
+    offset: usize,
+    end: usize,
+    locator: SourceCursor,
+    last_span: Span,
+    popped: usize,
+    failure: Option<Box<ParseFailure>>,
+}
+
+/// The furthest place in a queue that a check failed, and every kind of token that would have let
+/// it continue there. Checks try alternatives and back off, so the failure that got the furthest
+/// is usually the one that was meant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFailure {
+    index: usize,
+    pub expected: Vec<&'static str>,
+    pub found: String,
+    pub span: Span,
 }
 
 impl<'a, T> From<Lexer<'a, T>> for LibrettoTokenQueue<'a, T>
 where
-    T: Logos<'a> + PartialEq + Clone + Ordinal + Debug,
+    T: Logos<'a, Source = str> + PartialEq + Clone + Ordinal + Debug + 'a,
     T::Extras: Clone,
 {
     fn from(value: Lexer<'a, T>) -> Self {
-        LibrettoTokenQueue {
-            iterator: value.peekmore(),
-            cursor: 0,
-        }
+        let source = value.source();
+        LibrettoTokenQueue::nested(value, source, 0)
     }
 }
 
 impl<'a, T> PartialEq for LibrettoTokenQueue<'a, T>
 where
-    T: Logos<'a> + PartialEq + Clone + Ordinal + Debug,
+    T: Logos<'a, Source = str> + PartialEq + Clone + Ordinal + Debug,
     T::Extras: Clone,
 {
     fn eq(&self, other: &Self) -> bool {
@@ -44,13 +84,13 @@ where
 
 impl<'a, T> Debug for LibrettoTokenQueue<'a, T>
 where
-    T: Logos<'a> + PartialEq + Clone + Ordinal + Debug,
+    T: Logos<'a, Source = str> + PartialEq + Clone + Ordinal + Debug,
     T::Extras: Clone,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let proxy = self.iterator.clone();
         let mut formater = f.debug_list();
-        for token in proxy {
+        for (token, _) in proxy {
             formater.entry(&token);
         }
         formater.finish()
@@ -59,9 +99,88 @@ where
 
 impl<'a, T> LibrettoTokenQueue<'a, T>
 where
-    T: Logos<'a> + PartialEq + Clone + Ordinal + Debug + Debug + 'a,
+    T: Logos<'a, Source = str> + PartialEq + Clone + Ordinal + Debug + Debug + 'a,
     T::Extras: Clone,
 {
+    /// Creates a queue for a lexer whose source starts `offset` bytes into `source`.
+    pub fn nested(lexer: Lexer<'a, T>, source: &'a str, offset: usize) -> Self {
+        let end = offset + lexer.source().len();
+        LibrettoTokenQueue {
+            iterator: SpannedLexer(lexer).peekmore(),
+            cursor: 0,
+            source,
+            offset,
+            end,
+            locator: SourceCursor::default(),
+            last_span: Span::locate(source, offset, offset),
+            popped: 0,
+            failure: None,
+        }
+    }
+
+    /// The span of the last token that was popped from the queue.
+    pub fn last_span(&self) -> Span {
+        self.last_span
+    }
+
+    /// The span of the next token in the queue. If the queue is empty, this is an empty span at the
+    /// end of the queue's source.
+    pub fn next_span(&mut self) -> Span {
+        let (start, end) = match self.iterator.peek_nth(0) {
+            Some((_, range)) => (self.offset + range.start, self.offset + range.end),
+            None => (self.end, self.end),
+        };
+        self.locator.clone().locate(self.source, start, end)
+    }
+
+    /// Takes the furthest failure recorded since the last time one was taken or cleared.
+    pub fn take_failure(&mut self) -> Option<ParseFailure> {
+        self.failure.take().map(|failure| *failure)
+    }
+
+    pub fn clear_failure(&mut self) {
+        self.failure = None;
+    }
+
+    /// Describes the token `n` places after the cursor, and where it is. Past the end of the queue,
+    /// this is the end of the script, or the end of the block for a nested queue.
+    pub fn describe_nth(&mut self, n: usize) -> (String, Span) {
+        let (found, start, end) = match self.iterator.peek_nth(n) {
+            Some((_, range)) => {
+                let slice = &self.source[self.offset + range.start..self.offset + range.end];
+                let line = slice.lines().next().unwrap_or_default();
+                let found = if line.chars().count() > 24 || line.len() < slice.len() {
+                    format!("`{}...`", line.chars().take(24).collect::<String>())
+                } else {
+                    format!("`{}`", line)
+                };
+                (found, self.offset + range.start, self.offset + range.end)
+            }
+            None if self.end == self.source.len() => ("the end of the script".to_string(), self.end, self.end),
+            None => ("the end of the block".to_string(), self.end, self.end),
+        };
+        (found, self.locator.clone().locate(self.source, start, end))
+    }
+
+    /// Records that the token `n` places after the cursor is not one of the expected kinds.
+    pub fn fail_at(&mut self, n: usize, expected: &[&'static str]) {
+        let index = self.popped + n;
+        match &mut self.failure {
+            Some(failure) if failure.index > index => {}
+            Some(failure) if failure.index == index => {
+                for kind in expected {
+                    if !failure.expected.contains(kind) {
+                        failure.expected.push(kind);
+                    }
+                }
+            }
+            _ => {
+                let (found, span) = self.describe_nth(n);
+                self.failure = Some(Box::new(ParseFailure { index, expected: expected.to_vec(), found, span }));
+            }
+        }
+    }
+
     pub fn rewind(&mut self) {
         self.cursor = 0;
     }
@@ -76,58 +195,75 @@ where
         self.cursor = 0;
     }
 
+    pub fn rewind_to(&mut self, cursor: usize) {
+        self.cursor = cursor;
+    }
+
     pub fn cursor(&self) -> usize {
         self.cursor
     }
 
+    /// Checks if there are no more tokens after the cursor.
+    pub fn is_exhausted(&mut self) -> bool {
+        self.iterator.peek_nth(self.cursor).is_none()
+    }
+
     /// Gives the count of the queue. WARNING: This clones the iterator. Very Slow
     pub fn length(&self) -> usize {
         self.iterator.clone().count()
     }
 
-    pub fn next_is<D: From<T> + PartialEq + Copy>(
+    pub fn next_is<D: From<T> + PartialEq + Copy + Describe>(
         &mut self,
         ordinal_group: impl Into<OrdinalGroup<'a, T, D>>,
     ) -> bool {
-        let next = self.iterator.peek_nth(self.cursor);
-        if next.is_none() {
-            return false;
-        }
-        let t = next.unwrap();
         let ordinal_group: OrdinalGroup<'a, T, D> = ordinal_group.into();
-        let next_is = ordinal_group.check_ordinal(t);
+        let next_is = match self.iterator.peek_nth(self.cursor) {
+            Some((t, _)) => ordinal_group.check_ordinal(t),
+            None => false,
+        };
         if next_is {
             self.cursor += 1
-        };
+        } else {
+            self.fail_at(self.cursor, &ordinal_group.describe());
+        }
         next_is
     }
 
-    pub fn next_nth_is<D: From<T> + PartialEq + Copy>(
+    pub fn next_nth_is<D: From<T> + PartialEq + Copy + Describe>(
         &mut self,
         ordinal_group: impl Into<OrdinalGroup<'a, T, D>>,
         n: usize,
     ) -> bool {
-        let next = self.iterator.peek_nth(self.cursor + n);
-        if next.is_none() {
-            return false;
-        }
-        let t = next.unwrap();
         let ordinal_group: OrdinalGroup<'a, T, D> = ordinal_group.into();
-        let next_is = ordinal_group.check_ordinal(t);
+        let next_is = match self.iterator.peek_nth(self.cursor + n) {
+            Some((t, _)) => ordinal_group.check_ordinal(t),
+            None => false,
+        };
         if next_is {
             self.cursor += 1
-        };
+        } else {
+            self.fail_at(self.cursor + n, &ordinal_group.describe());
+        }
         next_is
     }
 
     pub fn pop(&mut self) -> Option<T> {
+        self.pop_spanned().map(|(token, _)| token)
+    }
+
+    /// Pops the next token along with where it is in the source.
+    pub fn pop_spanned(&mut self) -> Option<(T, Span)> {
         if self.cursor != 0 {
             self.cursor -= 1
         };
-        self.iterator.next()
+        let (token, range) = self.iterator.next()?;
+        self.popped += 1;
+        self.last_span = self.locator.locate(self.source, self.offset + range.start, self.offset + range.end);
+        Some((token, self.last_span))
     }
 
-    pub fn pop_if_next_is<D: From<T> + PartialEq + Copy>(
+    pub fn pop_if_next_is<D: From<T> + PartialEq + Copy + Describe>(
         &mut self,
         ordinal_group: impl Into<OrdinalGroup<'a, T, D>>,
     ) -> Option<T> {
@@ -139,7 +275,7 @@ where
         }
     }
 
-    pub fn pop_and_check_if<D: From<T> + PartialEq + Copy>(
+    pub fn pop_and_check_if<D: From<T> + PartialEq + Copy + Describe>(
         &mut self,
         ordinal_group: impl Into<OrdinalGroup<'a, T, D>>,
     ) -> bool {
@@ -151,7 +287,23 @@ where
         }
     }
 
-    pub fn pop_until<D: From<T> + PartialEq + Copy>(
+    /// Pops tokens until the next one is in the group, or the queue runs out. The token in the group
+    /// is left in the queue. This is how parsing gets back on track after a syntax error.
+    pub fn skip_until<D: From<T> + PartialEq + Copy>(
+        &mut self,
+        ordinal_group: impl Into<OrdinalGroup<'a, T, D>>,
+    ) {
+        self.reset();
+        let ordinal_group: OrdinalGroup<'a, T, D> = ordinal_group.into();
+        while let Some((token, _)) = self.iterator.peek_nth(0) {
+            if ordinal_group.check_ordinal(token) {
+                break;
+            }
+            self.pop();
+        }
+    }
+
+    pub fn pop_until<D: From<T> + PartialEq + Copy + Describe>(
         &mut self,
         ordinal_group: impl Into<OrdinalGroup<'a, T, D>>,
     ) -> Vec<T> {
@@ -242,6 +394,189 @@ where
     }
 }
 
+impl<'a, D, T> OrdinalGroup<'a, T, D>
+where
+    T: Logos<'a> + PartialEq + Clone + Ordinal,
+    T::Extras: Clone,
+    D: From<T> + PartialEq + Copy + Describe,
+{
+    fn describe(&self) -> Vec<&'static str> {
+        self.tokens.iter().map(|token| token.describe()).collect()
+    }
+}
+
+//==================================================================================================
+//          Describe - For Syntax Errors
+//==================================================================================================
+
+/// How a kind of token is named in a syntax error, like "a quote" or "`}`".
+pub trait Describe {
+    fn describe(&self) -> &'static str;
+}
+
+impl Describe for TokenOrdinal {
+    fn describe(&self) -> &'static str {
+        match self {
+            TokenOrdinal::Tag => "a tag",
+            TokenOrdinal::Speaker | TokenOrdinal::SpeakerLogic => "a speaker",
+            TokenOrdinal::Logic => "a logic block",
+            TokenOrdinal::Quote => "a quote",
+            TokenOrdinal::Bar => "`|`",
+            TokenOrdinal::LeftCurlyBracket => "`{`",
+            TokenOrdinal::RightCurlyBracket => "`}`",
+            TokenOrdinal::Arrow => "`->`",
+            TokenOrdinal::Dash => "`--`",
+            TokenOrdinal::Request => "`request`",
+            TokenOrdinal::Call => "`call`",
+            TokenOrdinal::Identifier => "a name",
+            TokenOrdinal::Path => "a path",
+            TokenOrdinal::Label => "a label",
+            TokenOrdinal::Comment => "a comment",
+            TokenOrdinal::Whitespace => "whitespace",
+            TokenOrdinal::Error => "an unknown token",
+        }
+    }
+}
+
+impl Describe for LogicOrdinal {
+    fn describe(&self) -> &'static str {
+        match self {
+            LogicOrdinal::Identifier => "a name",
+            LogicOrdinal::IntLiteral => "an int",
+            LogicOrdinal::FloatLiteral => "a float",
+            LogicOrdinal::BoolLiteral => "a bool",
+            LogicOrdinal::NoneLiteral => "`none`",
+            LogicOrdinal::Type => "a type",
+            LogicOrdinal::StringLiteral => "a string",
+            LogicOrdinal::Function => "`function`",
+            LogicOrdinal::If => "`if`",
+            LogicOrdinal::Else => "`else`",
+            LogicOrdinal::For => "`for`",
+            LogicOrdinal::In => "`in`",
+            LogicOrdinal::Let => "`let`",
+            LogicOrdinal::Const => "`const`",
+            LogicOrdinal::Yield => "`yield`",
+            LogicOrdinal::Wait => "`wait`",
+            LogicOrdinal::Request => "`request`",
+            LogicOrdinal::LeftCurlyBracket => "`{`",
+            LogicOrdinal::RightCurlyBracket => "`}`",
+            LogicOrdinal::LeftBracket => "`[`",
+            LogicOrdinal::RightBracket => "`]`",
+            LogicOrdinal::LeftParen => "`(`",
+            LogicOrdinal::RightParen => "`)`",
+            LogicOrdinal::Period => "`.`",
+            LogicOrdinal::Bang => "`!`",
+            LogicOrdinal::Question => "`?`",
+            LogicOrdinal::Comma => "`,`",
+            LogicOrdinal::Colon => "`:`",
+            LogicOrdinal::Semicolon => "`;`",
+            LogicOrdinal::InverseEquality => "`!=`",
+            LogicOrdinal::Equality => "`==`",
+            LogicOrdinal::LessThanEquality => "`<=`",
+            LogicOrdinal::GreaterThanEquality => "`>=`",
+            LogicOrdinal::Arrow => "`->`",
+            LogicOrdinal::LessThan => "`<`",
+            LogicOrdinal::GreaterThan => "`>`",
+            LogicOrdinal::Add => "`+`",
+            LogicOrdinal::Sub => "`-`",
+            LogicOrdinal::Mult => "`*`",
+            LogicOrdinal::Div => "`/`",
+            LogicOrdinal::Equals => "`=`",
+            LogicOrdinal::Whitespace => "whitespace",
+            LogicOrdinal::Error => "an unknown token",
+        }
+    }
+}
+
+impl Describe for QuoteOrdinal {
+    fn describe(&self) -> &'static str {
+        match self {
+            QuoteOrdinal::RightBracket => "`]`",
+            QuoteOrdinal::Logic => "a logic block",
+            QuoteOrdinal::Error => "an unknown token",
+        }
+    }
+}
+
+//==================================================================================================
+//          String Literals
+//==================================================================================================
+
+/// Replaces the escapes in a string or a quote: `\"`, `\\`, `\n`, `\r`, `\t`, and `\u{...}` with
+/// the hex code of a character. Any other escape is a lex error, so this gives `None`.
+pub(crate) fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '"' => unescaped.push('"'),
+            '\\' => unescaped.push('\\'),
+            'n' => unescaped.push('\n'),
+            'r' => unescaped.push('\r'),
+            't' => unescaped.push('\t'),
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+                let (code, rest) = chars.as_str().split_once('}')?;
+                if code.is_empty() || code.len() > 6 || !code.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return None;
+                }
+                unescaped.push(char::from_u32(u32::from_str_radix(code, 16).ok()?)?);
+                chars = rest.chars();
+            }
+            _ => return None,
+        }
+    }
+    Some(unescaped)
+}
+
+/// The text of a raw string, written as `r"..."`, or as `r#"..."#` when it has quotes in it. More
+/// `#` can be added when the text has `"#` in it as well.
+fn raw_content(slice: &str) -> String {
+    let hashes = slice[1..].chars().take_while(|c| *c == '#').count();
+    slice[hashes + 2..slice.len() - hashes - 1].to_string()
+}
+
+/// Finishes a raw string that opened with `r` and one or more `#` before its `"`, which the regex
+/// can't match on its own since its text can have quotes in it. The string closes at a `"` followed
+/// by as many `#` as it opened with. Gives `None` when the string is never closed.
+fn bump_raw_string<'a, T: Logos<'a, Source = str>>(lex: &mut Lexer<'a, T>) -> Option<String> {
+    let opening = lex.slice();
+    let closing = format!("\"{}", &opening[1..opening.len() - 1]);
+    let end = lex.remainder().find(&closing)?;
+    lex.bump(end + closing.len());
+    Some(raw_content(lex.slice()))
+}
+
+/// Lines up the text of a triple quoted quote. The line the quote opens on and the line it closes
+/// on are dropped when there is nothing else on them, and the indentation every other line shares
+/// is removed, so the text can be indented along with the script around it.
+fn strip_indentation(text: &str) -> String {
+    let mut lines: Vec<&str> = text.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)).collect();
+    if lines.len() > 1 && lines[0].trim().is_empty() {
+        lines.remove(0);
+    }
+    if lines.len() > 1 && lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = lines
+        .into_iter()
+        .map(|line| if line.trim().is_empty() { "" } else { &line[indentation..] })
+        .collect();
+    lines.join("\n")
+}
+
 //==================================================================================================
 //          Libretto Token - Top Level Lexing
 //==================================================================================================
@@ -250,9 +585,41 @@ fn content_after_first<'a>(lex: &mut Lexer<'a, LibrettoToken<'a>>) -> String {
     lex.slice()[1..].to_string()
 }
 
-fn content_except_first_and_last<'a>(lex: &mut Lexer<'a, LibrettoToken<'a>>) -> String {
+fn lex_quote<'a>(lex: &mut Lexer<'a, LibrettoToken<'a>>) -> Option<String> {
     let content = lex.slice();
-    content[1..content.len() - 1].to_string()
+    unescape(&content[1..content.len() - 1])
+}
+
+fn lex_raw_quote<'a>(lex: &mut Lexer<'a, LibrettoToken<'a>>) -> String {
+    raw_content(lex.slice())
+}
+
+/// Finishes a triple quoted quote that opened with `"""`. It closes at the first run of three or
+/// more quotes that aren't escaped, on the last three of them, so the text can end with a quote.
+/// Gives `None` when the quote is never closed.
+fn bump_multiline_quote<'a>(lex: &mut Lexer<'a, LibrettoToken<'a>>) -> Option<String> {
+    let bytes = lex.remainder().as_bytes();
+    let mut index = 0;
+    let end = loop {
+        match bytes.get(index)? {
+            b'\\' => index += 2,
+            b'"' => {
+                let run = bytes[index..].iter().take_while(|byte| **byte == b'"').count();
+                if run >= 3 {
+                    break index + run;
+                }
+                index += run;
+            }
+            _ => index += 1,
+        }
+    };
+    lex.bump(end);
+    let content = lex.slice();
+    unescape(&strip_indentation(&content[3..content.len() - 3]))
+}
+
+fn lex_identifier<'a>(lex: &mut Lexer<'a, LibrettoToken<'a>>) -> String {
+    lex.slice().to_string()
 }
 
 fn as_logic_for_top<'a>(
@@ -261,24 +628,41 @@ fn as_logic_for_top<'a>(
     let content = lex.slice();
     let content = &content[1..content.len() - 1];
     let logic_lex = LibrettoLogicToken::lexer(content);
-    logic_lex.into()
+    LibrettoTokenQueue::nested(logic_lex, lex.source(), lex.span().start + 1)
+}
+
+fn as_logic_for_speaker<'a>(
+    lex: &mut Lexer<'a, LibrettoToken<'a>>,
+) -> LibrettoTokenQueue<'a, LibrettoLogicToken> {
+    let content = lex.slice();
+    let content = &content[2..content.len() - 1];
+    let logic_lex = LibrettoLogicToken::lexer(content);
+    LibrettoTokenQueue::nested(logic_lex, lex.source(), lex.span().start + 2)
 }
 
 impl<'a> Ordinal for LibrettoToken<'a> {}
 
-#[derive(Logos, PartialEq, EnumDiscriminants, Clone)]
+#[derive(Debug, Logos, PartialEq, EnumDiscriminants, Clone)]
 #[strum_discriminants(name(TokenOrdinal))]
 pub enum LibrettoToken<'a> {
     #[regex("#([^ \t\n]*)", content_after_first)]
     Tag(String),
 
-    #[regex(":([^ \t\n]*)", content_after_first)]
+    #[regex(":([^ \t\n<][^ \t\n]*)?", content_after_first)]
     Speaker(String),
 
+    #[regex(":<([^><]*)>", as_logic_for_speaker)]
+    SpeakerLogic(LibrettoTokenQueue<'a, LibrettoLogicToken>),
+
     #[regex("<([^><]*)>", as_logic_for_top)]
     Logic(LibrettoTokenQueue<'a, LibrettoLogicToken>),
 
-    #[regex("\"([^\"]*)\"", content_except_first_and_last)]
+    /// The text of a line. Quotes can be written as `"..."` with escapes, as raw `r"..."` or
+    /// `r#"..."#` without them, or as `"""..."""` over several lines.
+    #[regex(r#""([^"\\]|\\[^\n])*""#, lex_quote)]
+    #[regex(r#"r"[^"]*""#, lex_raw_quote)]
+    #[regex(r#"r#+""#, bump_raw_string)]
+    #[token(r#"""""#, bump_multiline_quote)]
     Quote(String),
 
     #[token("|")]
@@ -299,6 +683,18 @@ pub enum LibrettoToken<'a> {
     #[token("request")]
     Request,
 
+    #[token("call")]
+    Call,
+
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*", lex_identifier)]
+    Identifier(String),
+
+    #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*\.[a-zA-Z_][a-zA-Z0-9_]*", lex_identifier)]
+    Path(String),
+
+    #[regex("@[a-zA-Z_][a-zA-Z0-9_]*", content_after_first)]
+    Label(String),
+
     #[regex(r"//[^\n\r]+(?:\*\)|[\n\r])", logos::skip)]
     Comment,
 
@@ -313,23 +709,45 @@ pub enum LibrettoToken<'a> {
 //          Libretto Logic Token - Logic Level Lexing
 //==================================================================================================
 
-fn lex_string(lex: &mut Lexer<LibrettoLogicToken>) -> String {
+fn lex_string(lex: &mut Lexer<LibrettoLogicToken>) -> Option<String> {
     let content = lex.slice();
-    content[1..content.len() - 1].to_string()
+    unescape(&content[1..content.len() - 1])
+}
+
+fn lex_raw_string(lex: &mut Lexer<LibrettoLogicToken>) -> String {
+    raw_content(lex.slice())
 }
 
 fn lex_text(lex: &mut Lexer<LibrettoLogicToken>) -> String {
     lex.slice().to_string()
 }
 
-fn lex_int(lex: &mut Lexer<LibrettoLogicToken>) -> i64 {
-    let content = lex.slice().to_string();
-    content.parse::<i64>().unwrap()
+/// Reads the magnitude of an int literal, which can be written in decimal, in hex after `0x`, or in
+/// binary after `0b`, with `_` anywhere after the first digit. The sign is not part of the literal.
+/// A literal too large for a `u64` is out of range for an int whatever its sign, so it is a lex
+/// error, as is a literal without digits, like `0x_`.
+fn lex_int(lex: &mut Lexer<LibrettoLogicToken>) -> Option<u64> {
+    let content = lex.slice();
+    let (digits, radix) = match content.get(..2) {
+        Some("0x" | "0X") => (&content[2..], 16),
+        Some("0b" | "0B") => (&content[2..], 2),
+        _ => (content, 10),
+    };
+    let mut value: u64 = 0;
+    let mut any_digits = false;
+    for c in digits.chars().filter(|c| *c != '_') {
+        let digit = c.to_digit(radix)?;
+        any_digits = true;
+        value = value.checked_mul(radix as u64)?.checked_add(digit as u64)?;
+    }
+    any_digits.then_some(value)
 }
 
-fn lex_float(lex: &mut Lexer<LibrettoLogicToken>) -> f64 {
-    let content = lex.slice().to_string();
-    content.parse::<f64>().unwrap()
+/// Reads a float literal, like `1.5`, `.5`, `1e-3` or `2.5E+10`, with `_` allowed between digits.
+/// Literals too large for an `f64` are a lex error, rather than being read as infinity.
+fn lex_float(lex: &mut Lexer<LibrettoLogicToken>) -> Option<f64> {
+    let content: String = lex.slice().chars().filter(|c| *c != '_').collect();
+    content.parse::<f64>().ok().filter(|value| value.is_finite())
 }
 
 fn lex_bool(lex: &mut Lexer<LibrettoLogicToken>) -> bool {
@@ -359,10 +777,15 @@ pub enum LibrettoLogicToken {
     #[regex("[a-zA-Z0-9_]+", lex_text, priority = 1)]
     Identifier(String),
 
-    #[regex("[0-9]+", lex_int, priority = 2)]
-    IntLiteral(i64),
+    /// The magnitude of an int. A `-` in front of it is folded into the literal by the parser.
+    #[regex("[0-9][0-9_]*", lex_int, priority = 2)]
+    #[regex("0[xX][0-9a-fA-F_]+", lex_int, priority = 2)]
+    #[regex("0[bB][01_]+", lex_int, priority = 2)]
+    IntLiteral(u64),
 
-    #[regex(r"([0-9]+)\.([0-9]+)", lex_float, priority = 3)]
+    #[regex(r"[0-9][0-9_]*\.[0-9][0-9_]*([eE][+-]?[0-9][0-9_]*)?", lex_float, priority = 3)]
+    #[regex(r"\.[0-9][0-9_]*([eE][+-]?[0-9][0-9_]*)?", lex_float, priority = 3)]
+    #[regex(r"[0-9][0-9_]*[eE][+-]?[0-9][0-9_]*", lex_float, priority = 3)]
     FloatLiteral(f64),
 
     #[regex("(true|false)", lex_bool, priority=4)]
@@ -374,7 +797,9 @@ pub enum LibrettoLogicToken {
     #[regex("(float|int|string|bool|struct|array|function)", lex_type, priority=4)]
     Type(LsonType),
 
-    #[regex("\"([^\"]*)\"", lex_string)]
+    #[regex(r#""([^"\\]|\\[^\n])*""#, lex_string)]
+    #[regex(r#"r"[^"]*""#, lex_raw_string)]
+    #[regex(r#"r#+""#, bump_raw_string)]
     StringLiteral(String),
 
     #[token("function")]
@@ -398,6 +823,15 @@ pub enum LibrettoLogicToken {
     #[token("const")]
     Const,
 
+    #[token("yield")]
+    Yield,
+
+    #[token("wait")]
+    Wait,
+
+    #[token("request")]
+    Request,
+
     #[token("{")]
     LeftCurlyBracket,
 
@@ -483,11 +917,11 @@ pub enum LibrettoLogicToken {
 
 fn as_logic_for_quote<'a>(
     lex: &mut Lexer<'a, LibrettoQuoteToken<'a>>,
-) -> LibrettoTokenQueue<'a, LibrettoLogicToken> {
+) -> Box<LibrettoTokenQueue<'a, LibrettoLogicToken>> {
     let content = lex.slice();
     let content = &content[1..content.len() - 1];
     let logic_lex = LibrettoLogicToken::lexer(content);
-    logic_lex.into()
+    Box::new(LibrettoTokenQueue::nested(logic_lex, lex.source(), lex.span().start + 1))
 }
 
 impl<'a> Ordinal for LibrettoQuoteToken<'a> {}
@@ -501,7 +935,7 @@ pub enum LibrettoQuoteToken<'a> {
     RightBracket,
 
     #[regex(r"<([^><]*)>", as_logic_for_quote)]
-    Logic(LibrettoTokenQueue<'a, LibrettoLogicToken>),
+    Logic(Box<LibrettoTokenQueue<'a, LibrettoLogicToken>>),
 
     #[error]
     Error,
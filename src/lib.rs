@@ -1,6 +1,24 @@
+// Lets the derive macros name this crate as `::libretto` from inside of it as well.
+extern crate self as libretto;
+
+mod diagnostic;
 mod lexer;
-mod lson;
+pub mod lson;
 mod parse;
 mod runtime;
 mod scope;
-mod compiler;
\ No newline at end of file
+mod script;
+mod speaker;
+mod span;
+mod compiler;
+
+pub use compiler::{compile, LibrettoCompiletime, LibrettoLint, LibrettoLintConfig, LintLevel};
+pub use diagnostic::{Diagnostic, DiagnosticRenderer, Diagnostics, Severity};
+pub use lson::{Lson, LsonMap, LsonType};
+pub use runtime::{
+    dialog::{LibrettoLine, LibrettoResponse, LibrettoStep},
+    LibrettoRuntime, LibrettoRuntimeError, LibrettoRuntimeErrorKind, LibrettoSaveState,
+};
+pub use script::{LibrettoPackageError, LibrettoScript, FORMAT_VERSION};
+pub use span::Span;
+pub use speaker::{LibrettoSpeaker, LibrettoSpeakerRegistry};
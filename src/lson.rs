@@ -1,3 +1,28 @@
+mod convert;
+mod de;
+mod diff;
+mod display;
+mod json;
+mod macros;
+mod path;
+mod ser;
+
+pub use convert::{FromLson, IntoLson, LsonSchema};
+#[cfg(feature = "derive")]
+pub use libretto_derive::{FromLson, IntoLson};
+#[doc(hidden)]
+pub use convert::{take_field, take_struct, take_tuple, take_variant};
+pub use de::from_value;
+pub use diff::{LsonChange, LsonPatch};
+pub use display::LsonDisplay;
+pub use path::{LsonPath, LsonPathError, LsonPathSegment};
+pub use ser::to_value;
+
+/// The map behind [`LsonMap`], for naming it and its iterators.
+pub use indexmap;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use strum::EnumDiscriminants;
 
 use crate::runtime::LibrettoRuntime;
@@ -6,13 +31,23 @@ use std::{
     collections::HashMap,
     fmt::{Debug, Display},
     ops::{self},
-    rc::Rc, cmp,
+    sync::Arc, cmp,
 };
 
-pub type LibrettoFunction = Rc<dyn Fn(Vec<Lson>, &mut LibrettoRuntime) -> Lson>;
+/// A function the host gives to a script. It is shared between threads along with the values that
+/// hold it, so a compiled script can be run by many runtimes at once.
+pub type LibrettoFunction = Arc<dyn Fn(Vec<Lson>, &mut LibrettoRuntime) -> Lson + Send + Sync>;
 
+/// The fields of a struct. They keep the order they were added in, which is the order they are
+/// iterated, printed and serialized in. Removing a field with `shift_remove` keeps the order of
+/// the others. Two structs are equal when they have the same fields, in any order.
+pub type LsonMap = IndexMap<String, Lson>;
+
+/// A value of the logic language. The fields of a struct keep the order they were added in, so a
+/// struct written in a script, built with [`lson!`](crate::lson!) or serialized from a Rust struct
+/// iterates, prints and serializes its fields in the order they were written.
 #[derive(Clone, EnumDiscriminants)]
-#[strum_discriminants(name(LsonType))]
+#[strum_discriminants(name(LsonType), derive(Serialize, Deserialize))]
 pub enum Lson {
     None,
     Int(i64),
@@ -20,7 +55,7 @@ pub enum Lson {
     String(String),
     Bool(bool),
     Array(Vec<Lson>),
-    Struct(HashMap<String, Lson>),
+    Struct(LsonMap),
     Function(LibrettoFunction, LsonType),
 }
 
@@ -31,6 +66,15 @@ impl Default for Lson {
 }
 
 impl Lson {
+    /// Widens an int to a float when a float is expected, so the value matches the type it was
+    /// checked against. Every other value is returned as it is.
+    pub fn coerce_to(self, lson_type : LsonType) -> Lson {
+        match (self, lson_type) {
+            (Lson::Int(value), LsonType::Float) => Lson::Float(value as f64),
+            (value, _) => value,
+        }
+    }
+
     pub fn is_i64(&self) -> bool {
         if let Lson::Int(_) = self {
             true
@@ -224,37 +268,92 @@ impl ops::Div for Lson {
     }
 }
 
+//================================================================================================
+//          Comparison
+//================================================================================================
+
+/// Values are equal when they are of the same type and hold the same thing, except that an int
+/// and a float are equal when they are the same number. `NaN` is equal to itself, so that every
+/// value is equal to itself. Structs are equal when they have the same fields, in any order, and
+/// functions are equal only to themselves.
 impl PartialEq for Lson {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Self::Int(l0), Self::Int(r0)) => l0 == r0,
-            (Self::Float(l0), Self::Float(r0)) => l0 == r0,
-            (Self::Float(l0), Self::Int(r0)) => *l0 == *r0 as f64,
-            (Self::Int(l0), Self::Float(r0)) => *l0 as f64 == *r0,
-            (Self::String(l0), Self::String(r0)) => l0 == r0,
-            (Self::Bool(l0), Self::Bool(r0)) => l0 == r0,
-            (Self::Array(l0), Self::Array(r0)) => l0 == r0,
-            (Self::Struct(l0), Self::Struct(r0)) => l0 == r0,
-            (Self::Function(l0, l1), Self::Function(r0, r1)) => false,
-            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+            (Lson::Int(_) | Lson::Float(_), Lson::Int(_) | Lson::Float(_)) => self.compare(other) == Ok(cmp::Ordering::Equal),
+            (Lson::None, Lson::None) => true,
+            (Lson::String(l0), Lson::String(r0)) => l0 == r0,
+            (Lson::Bool(l0), Lson::Bool(r0)) => l0 == r0,
+            (Lson::Array(l0), Lson::Array(r0)) => l0 == r0,
+            (Lson::Struct(l0), Lson::Struct(r0)) => l0 == r0,
+            (Lson::Function(l0, _), Lson::Function(r0, _)) => Arc::ptr_eq(l0, r0),
+            _ => false,
         }
     }
 }
 
+/// Follows [`Lson::compare`], with values that can't be ordered giving `None`.
 impl PartialOrd for Lson {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.compare(other).ok()
+    }
+}
+
+impl Lson {
+    /// Orders two values. Ints and floats are ordered by the numbers they hold, without rounding
+    /// either one, and `NaN` comes after every other number. Strings are ordered by their
+    /// characters, `false` comes before `true`, and arrays are ordered by their first value that
+    /// differs, with a shorter array first when one is the start of the other. Any other pair of
+    /// values, like a string and an int or two structs, can't be ordered and gives back the types
+    /// of the pair that couldn't be, unless the two are equal, so that `compare` always agrees
+    /// with `==`.
+    pub fn compare(&self, other: &Lson) -> Result<cmp::Ordering, (LsonType, LsonType)> {
         match (self, other) {
-            (Lson::Float(v1), Lson::Float(v2)) => v1.partial_cmp(v2),
-            (Lson::Float(v1), Lson::Int(v2)) => v1.partial_cmp(&(*v2 as f64)),
-            (Lson::Int(v1), Lson::Float(v2)) => v1.partial_cmp(&(*v2 as i64)),
-            (Lson::Int(v1), Lson::Int(v2)) => v1.partial_cmp(v2),
-            (Lson::Bool(v1), Lson::Bool(v2)) => v1.partial_cmp(v2),
-            (Lson::String(v1), Lson::String(v2)) => v1.partial_cmp(v2),
-            _ => Some(cmp::Ordering::Equal)
+            (Lson::Int(l0), Lson::Int(r0)) => Ok(l0.cmp(r0)),
+            (Lson::Float(l0), Lson::Float(r0)) => Ok(compare_floats(*l0, *r0)),
+            (Lson::Int(l0), Lson::Float(r0)) => Ok(compare_int_to_float(*l0, *r0)),
+            (Lson::Float(l0), Lson::Int(r0)) => Ok(compare_int_to_float(*r0, *l0).reverse()),
+            (Lson::String(l0), Lson::String(r0)) => Ok(l0.cmp(r0)),
+            (Lson::Bool(l0), Lson::Bool(r0)) => Ok(l0.cmp(r0)),
+            (Lson::None, Lson::None) => Ok(cmp::Ordering::Equal),
+            (Lson::Array(l0), Lson::Array(r0)) => {
+                for (left, right) in l0.iter().zip(r0) {
+                    match left.compare(right)? {
+                        cmp::Ordering::Equal => {},
+                        ordering => return Ok(ordering),
+                    }
+                }
+                Ok(l0.len().cmp(&r0.len()))
+            },
+            (left, right) if left == right => Ok(cmp::Ordering::Equal),
+            (left, right) => Err((left.get_type(), right.get_type())),
         }
     }
 }
 
+fn compare_floats(left: f64, right: f64) -> cmp::Ordering {
+    match (left.is_nan(), right.is_nan()) {
+        (true, true) => cmp::Ordering::Equal,
+        (true, false) => cmp::Ordering::Greater,
+        (false, true) => cmp::Ordering::Less,
+        (false, false) => left.partial_cmp(&right).unwrap(),
+    }
+}
+
+/// Compares an int to a float exactly. Converting the int to a float would round ints past 2^53,
+/// and converting the float to an int would drop its fraction.
+fn compare_int_to_float(int: i64, float: f64) -> cmp::Ordering {
+    // 2^63, the first float past the largest int.
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    if float.is_nan() || float >= LIMIT {
+        return cmp::Ordering::Less;
+    }
+    if float < -LIMIT {
+        return cmp::Ordering::Greater;
+    }
+    let whole = float.trunc();
+    int.cmp(&(whole as i64)).then_with(|| compare_floats(whole, float))
+}
+
  impl From<i64> for Lson {
     fn from(value: i64) -> Self {
         Lson::Int(value)
@@ -323,23 +422,26 @@ impl<T: Into<Lson>, const COUNT: usize> From<[T; COUNT]> for Lson {
     }
 }
 
+impl<T: Into<Lson>> From<IndexMap<String, T>> for Lson {
+    fn from(value: IndexMap<String, T>) -> Self {
+        Lson::Struct(value.into_iter().map(|(key, value)| (key, value.into())).collect())
+    }
+}
+
+/// A `HashMap` has no order of its own, so its fields are sorted by name.
 impl<T: Into<Lson>> From<HashMap<String, T>> for Lson {
     fn from(value: HashMap<String, T>) -> Self {
-        let res: HashMap<String, Lson> = value
-            .into_iter()
-            .map(|(key, value)| (key, value.into()))
-            .collect();
-        Lson::Struct(res)
+        let mut fields: Vec<(String, T)> = value.into_iter().collect();
+        fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Lson::Struct(fields.into_iter().map(|(key, value)| (key, value.into())).collect())
     }
 }
 
 impl<T: Into<Lson>> From<HashMap<&str, T>> for Lson {
     fn from(value: HashMap<&str, T>) -> Self {
-        let res: HashMap<String, Lson> = value
-            .into_iter()
-            .map(|(key, value)| (key.to_string(), value.into()))
-            .collect();
-        Lson::Struct(res)
+        let mut fields: Vec<(&str, T)> = value.into_iter().collect();
+        fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Lson::Struct(fields.into_iter().map(|(key, value)| (key.to_string(), value.into())).collect())
     }
 }
 
@@ -357,61 +459,61 @@ impl PartialEq<String> for Lson {
 
 impl PartialEq<i64> for Lson {
     fn eq(&self, other: &i64) -> bool {
-        self.as_i64().map_or(false, |i| i == *other)
+        *self == Lson::Int(*other)
     }
 }
 
 impl PartialEq<i32> for Lson {
     fn eq(&self, other: &i32) -> bool {
-        self.as_i64().map_or(false, |i| i == (*other) as i64)
+        *self == Lson::Int(i64::from(*other))
     }
 }
 
 impl PartialEq<i16> for Lson {
     fn eq(&self, other: &i16) -> bool {
-        self.as_i64().map_or(false, |i| i == (*other) as i64)
+        *self == Lson::Int(i64::from(*other))
     }
 }
 
 impl PartialEq<i8> for Lson {
     fn eq(&self, other: &i8) -> bool {
-        self.as_i64().map_or(false, |i| i == (*other) as i64)
+        *self == Lson::Int(i64::from(*other))
     }
 }
 
 impl PartialEq<u64> for Lson {
     fn eq(&self, other: &u64) -> bool {
-        self.as_i64().map_or(false, |i| i == (*other) as i64)
+        i64::try_from(*other).map_or(false, |other| *self == Lson::Int(other))
     }
 }
 
 impl PartialEq<u32> for Lson {
     fn eq(&self, other: &u32) -> bool {
-        self.as_i64().map_or(false, |i| i == (*other) as i64)
+        *self == Lson::Int(i64::from(*other))
     }
 }
 
 impl PartialEq<u16> for Lson {
     fn eq(&self, other: &u16) -> bool {
-        self.as_i64().map_or(false, |i| i == (*other) as i64)
+        *self == Lson::Int(i64::from(*other))
     }
 }
 
 impl PartialEq<u8> for Lson {
     fn eq(&self, other: &u8) -> bool {
-        self.as_i64().map_or(false, |i| i == (*other) as i64)
+        *self == Lson::Int(i64::from(*other))
     }
 }
 
 impl PartialEq<f64> for Lson {
     fn eq(&self, other: &f64) -> bool {
-        self.as_f64().map_or(false, |f| f == (*other))
+        *self == Lson::Float(*other)
     }
 }
 
 impl PartialEq<f32> for Lson {
     fn eq(&self, other: &f32) -> bool {
-        self.as_f64().map_or(false, |f| f == (*other) as f64)
+        *self == Lson::Float(f64::from(*other))
     }
 }
 
@@ -455,6 +557,26 @@ impl Debug for Lson {
 //================================================================================================
 
 impl LsonType {
+    /// The value a variable declared with this type holds before anything is assigned to it, so
+    /// that it has the declared type from the start. Functions have no such value, and are none.
+    pub fn default_value(&self) -> Lson {
+        match self {
+            LsonType::Int => Lson::Int(0),
+            LsonType::Float => Lson::Float(0.0),
+            LsonType::String => Lson::String(String::new()),
+            LsonType::Bool => Lson::Bool(false),
+            LsonType::Array => Lson::Array(Vec::new()),
+            LsonType::Struct => Lson::Struct(LsonMap::new()),
+            LsonType::None | LsonType::Function => Lson::None,
+        }
+    }
+
+    /// Whether a value of the other type can be stored where this type is expected. Ints widen to
+    /// floats without losing anything, so a float accepts an int, but not the other way around.
+    pub fn accepts(&self, other : LsonType) -> bool {
+        *self == other || (*self == LsonType::Float && other == LsonType::Int)
+    }
+
     pub fn get_sum_type(&self, other : LsonType) -> LsonType {
         match (self, other) {
             (LsonType::Float, LsonType::Float) |
@@ -502,7 +624,11 @@ impl LsonType {
             (LsonType::Float, LsonType::Float) |
             (LsonType::Int, LsonType::Int) |
             (LsonType::Int, LsonType::Float) |
-            (LsonType::Float, LsonType::Int) => LsonType::Bool,
+            (LsonType::Float, LsonType::Int) |
+            (LsonType::String, LsonType::String) |
+            (LsonType::Bool, LsonType::Bool) |
+            (LsonType::None, LsonType::None) |
+            (LsonType::Array, LsonType::Array) => LsonType::Bool,
             _ => LsonType::None
         }
     }
@@ -517,7 +643,9 @@ impl LsonType {
             (LsonType::Bool, LsonType::Bool) |
             (LsonType::Array, LsonType::Array) |
             (LsonType::Struct, LsonType::Struct) |
-            (LsonType::Function, LsonType::Function) => LsonType::Bool,
+            (LsonType::Function, LsonType::Function) |
+            (LsonType::None, _) |
+            (_, LsonType::None) => LsonType::Bool,
             _ => LsonType::None
         }
     }
@@ -604,7 +732,7 @@ impl LsonIndex for str {
 
     fn index_or_insert<'l>(&self, value: &'l mut Lson) -> &'l mut Lson {
         if let Lson::None = value {
-            *value = Lson::Struct(HashMap::new());
+            *value = Lson::Struct(LsonMap::new());
         }
         match value {
             Lson::Struct(map) => map.entry(self.to_owned()).or_insert(Lson::None),
@@ -644,6 +772,176 @@ where
     }
 }
 
+//================================================================================================
+//          Lson Error
+//================================================================================================
+
+/// An error from moving a value between Rust and Lson with serde.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum LsonError {
+    #[error("{0}")]
+    Custom(String),
+
+    #[error("A function can't be serialized. Functions come from the host and only exist while it runs.")]
+    Function,
+
+    #[error("The int {0} doesn't fit in the 64 bits of an Lson int.")]
+    IntOutOfRange(String),
+
+    #[error("The keys of a struct must be strings, but a key is of type {0}.")]
+    KeyNotString(String),
+
+    #[error("An enum variant must be a string or a struct with a single field, but it is of type {0}.")]
+    NotAVariant(String),
+
+    #[error("There is no variant named '{0}'.")]
+    UnknownVariant(String),
+
+    #[error("Expected a value of type {0}, but found a value of type {1}.")]
+    WrongType(String, String),
+
+    #[error("Expected an array with {0} values, but found {1}.")]
+    WrongLength(usize, usize),
+
+    #[error("The struct has no field named '{0}'.")]
+    MissingField(String),
+
+    #[error("In the field '{0}': {1}")]
+    InField(String, Box<LsonError>),
+
+    #[error(transparent)]
+    Path(#[from] LsonPathError),
+
+    #[error("'{0}' is not a literal value.")]
+    NotALiteral(String),
+
+    #[error("The JSON is not valid at line {0}, column {1}: {2}.")]
+    Json(usize, usize, String),
+
+    #[error("The float {0} has no JSON form.")]
+    NotFinite(String),
+}
+
+impl serde::ser::Error for LsonError {
+    fn custom<T: Display>(msg: T) -> Self {
+        LsonError::Custom(msg.to_string())
+    }
+}
+
+impl serde::de::Error for LsonError {
+    fn custom<T: Display>(msg: T) -> Self {
+        LsonError::Custom(msg.to_string())
+    }
+}
+
+//================================================================================================
+//          Tagged
+//================================================================================================
+
+/// The form values take inside of a compiled script, like the literals of an expression. Every
+/// value is written along with its kind, so that formats which can't describe themselves, like
+/// the binary format of a packaged script, can read them back. Struct fields are written in the
+/// order they were added, so the same script always gives the same bytes. Functions only ever come from
+/// the host, and fail to serialize.
+pub(crate) mod tagged {
+    use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Lson;
+
+    #[derive(Serialize, Deserialize)]
+    enum TaggedLson {
+        None,
+        Int(i64),
+        Float(f64),
+        String(String),
+        Bool(bool),
+        Array(Vec<TaggedLson>),
+        Struct(Vec<(String, TaggedLson)>),
+    }
+
+    impl TryFrom<&Lson> for TaggedLson {
+        type Error = &'static str;
+
+        fn try_from(value: &Lson) -> Result<Self, Self::Error> {
+            Ok(match value {
+                Lson::None => TaggedLson::None,
+                Lson::Int(value) => TaggedLson::Int(*value),
+                Lson::Float(value) => TaggedLson::Float(*value),
+                Lson::String(value) => TaggedLson::String(value.clone()),
+                Lson::Bool(value) => TaggedLson::Bool(*value),
+                Lson::Array(values) => TaggedLson::Array(values.iter().map(TaggedLson::try_from).collect::<Result<_, _>>()?),
+                Lson::Struct(fields) => {
+                    let fields = fields.iter()
+                        .map(|(key, value)| Ok((key.clone(), TaggedLson::try_from(value)?)))
+                        .collect::<Result<Vec<_>, Self::Error>>()?;
+                    TaggedLson::Struct(fields)
+                },
+                Lson::Function(..) => return Err("a function can't be serialized, as part of a compiled script or a save"),
+            })
+        }
+    }
+
+    impl From<TaggedLson> for Lson {
+        fn from(value: TaggedLson) -> Self {
+            match value {
+                TaggedLson::None => Lson::None,
+                TaggedLson::Int(value) => Lson::Int(value),
+                TaggedLson::Float(value) => Lson::Float(value),
+                TaggedLson::String(value) => Lson::String(value),
+                TaggedLson::Bool(value) => Lson::Bool(value),
+                TaggedLson::Array(values) => Lson::Array(values.into_iter().map(Lson::from).collect()),
+                TaggedLson::Struct(fields) => Lson::Struct(fields.into_iter().map(|(key, value)| (key, Lson::from(value))).collect()),
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(value: &Lson, serializer: S) -> Result<S::Ok, S::Error> {
+        TaggedLson::try_from(value).map_err(S::Error::custom)?.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Lson, D::Error> {
+        TaggedLson::deserialize(deserializer).map(Lson::from)
+    }
+
+    /// Variables by name, like the global variables of a save. They are written in the order of
+    /// their names, so the same variables are always written the same way.
+    pub mod map {
+        use std::collections::HashMap;
+
+        use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+        use super::{Lson, TaggedLson};
+
+        pub fn serialize<S: Serializer>(value: &HashMap<String, Lson>, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut entries = value.iter()
+                .map(|(key, value)| Ok((key, TaggedLson::try_from(value)?)))
+                .collect::<Result<Vec<_>, &'static str>>()
+                .map_err(S::Error::custom)?;
+            entries.sort_by_key(|(key, _)| *key);
+            entries.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, Lson>, D::Error> {
+            Vec::<(String, TaggedLson)>::deserialize(deserializer)
+                .map(|entries| entries.into_iter().map(|(key, value)| (key, Lson::from(value))).collect())
+        }
+    }
+
+    pub mod option {
+        use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+        use super::{Lson, TaggedLson};
+
+        pub fn serialize<S: Serializer>(value: &Option<Lson>, serializer: S) -> Result<S::Ok, S::Error> {
+            value.as_ref().map(TaggedLson::try_from).transpose().map_err(S::Error::custom)?.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Lson>, D::Error> {
+            Option::<TaggedLson>::deserialize(deserializer).map(|value| value.map(Lson::from))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -685,4 +983,90 @@ mod tests {
         let array: Lson = HashMap::from([("test1", 10), ("test2", 20), ("test3", 30)]).into();
         assert_eq!(array["test1"], 10);
     }
+
+    #[test]
+    fn compare_numbers() {
+        use std::cmp::Ordering;
+
+        assert_eq!(Lson::Int(2).compare(&Lson::Float(2.5)), Ok(Ordering::Less));
+        assert_eq!(Lson::Float(-0.5).compare(&Lson::Int(0)), Ok(Ordering::Less));
+        assert_eq!(Lson::Int(-1).compare(&Lson::Float(-1.5)), Ok(Ordering::Greater));
+        assert_eq!(Lson::Int(i64::MAX).compare(&Lson::Float(9.223372036854776e18)), Ok(Ordering::Less));
+        assert_eq!(Lson::Int((1 << 53) + 1).compare(&Lson::Float((1u64 << 53) as f64)), Ok(Ordering::Greater));
+        assert_eq!(Lson::Float(f64::NAN).compare(&Lson::Float(f64::INFINITY)), Ok(Ordering::Greater));
+        assert_eq!(Lson::Float(-0.0), Lson::Float(0.0));
+        assert_eq!(Lson::Float(f64::NAN), Lson::Float(f64::NAN));
+        assert_eq!(Lson::Int(3), Lson::Float(3.0));
+        assert_ne!(Lson::Int(3), Lson::Float(3.000001));
+        assert_eq!(Lson::Int(3), 3.0);
+        assert_ne!(Lson::Int(-1), u64::MAX);
+    }
+
+    #[test]
+    fn compare_values() {
+        use std::{cmp::Ordering, sync::Arc};
+
+        use super::LsonType;
+
+        assert_eq!(Lson::from("apple").compare(&Lson::from("banana")), Ok(Ordering::Less));
+        assert!(Lson::Bool(false) < Lson::Bool(true));
+        assert!(crate::lson!([1, 2]) < crate::lson!([1, 2.5]));
+        assert!(crate::lson!([1, 2]) < crate::lson!([1, 2, 0]));
+        assert_eq!(
+            Lson::from("1").compare(&Lson::Int(1)),
+            Err((LsonType::String, LsonType::Int)),
+        );
+        assert!(crate::lson!({ a : 1 }).compare(&crate::lson!({ a : 2 })).is_err());
+        assert_eq!(Lson::from("1").partial_cmp(&Lson::Int(1)), None);
+        assert!(crate::lson!([1]).compare(&crate::lson!(["a"])).is_err());
+
+        assert_ne!(Lson::from("1"), Lson::Int(1));
+        assert_ne!(Lson::None, Lson::Bool(false));
+        let function = Lson::Function(Arc::new(|_, _| Lson::None), LsonType::None);
+        assert_eq!(function, function.clone());
+        assert_ne!(function, Lson::Function(Arc::new(|_, _| Lson::None), LsonType::None));
+
+        let (left, right) = (crate::lson!({ a : 1, b : 2 }), crate::lson!({ b : 2, a : 1.0 }));
+        assert_eq!(left.partial_cmp(&right), Some(Ordering::Equal));
+        assert!(left <= right && left >= right);
+        assert_eq!(function.partial_cmp(&function.clone()), Some(Ordering::Equal));
+        let nested = Lson::Array(vec![left.clone(), function.clone()]);
+        assert_eq!(nested.compare(&Lson::Array(vec![right, function])), Ok(Ordering::Equal));
+        assert_eq!(Lson::None.compare(&Lson::None), Ok(Ordering::Equal));
+    }
+
+    #[test]
+    fn struct_order() {
+        let mut value = crate::lson!({ zeta : 1, alpha : [true], mid : { b : 2, a : none } });
+        value["beta"] = Lson::Float(0.5);
+        assert_eq!(
+            format!("{:?}", value),
+            r#"Struct({"zeta": Int(1), "alpha": Array([Bool(true)]), "mid": Struct({"b": Int(2), "a": None}), "beta": Float(0.5)})"#,
+        );
+
+        let Lson::Struct(fields) = &mut value else { unreachable!() };
+        fields.shift_remove("alpha");
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["zeta", "mid", "beta"]);
+        assert_eq!(value, crate::lson!({ beta : 0.5, mid : { a : none, b : 2 }, zeta : 1 }));
+
+        let sorted: Lson = HashMap::from([("c", 3), ("a", 1), ("b", 2)]).into();
+        let Lson::Struct(fields) = sorted else { unreachable!() };
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn struct_order_serialized() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Literal(#[serde(with = "super::tagged")] Lson);
+
+        let value = crate::lson!({ zeta : 1, alpha : { y : 2, x : 3 } });
+        let bytes = bincode::serialize(&Literal(value.clone())).unwrap();
+        let Literal(read) = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(format!("{:?}", read), format!("{:?}", value));
+
+        #[derive(serde::Serialize)]
+        struct Save { version: u8, name: &'static str, flags: Vec<bool> }
+        let Lson::Struct(fields) = super::to_value(&Save { version: 1, name: "a", flags: vec![] }).unwrap() else { unreachable!() };
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["version", "name", "flags"]);
+    }
 }
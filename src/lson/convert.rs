@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use super::{Lson, LsonError, LsonMap, LsonType};
+
+//==================================================================================================
+//          Lson Schema
+//==================================================================================================
+
+/// The shape of a Rust type once it is turned into Lson. Where [`LsonType`] only says what kind of
+/// value something is, a schema also knows the fields of a struct and what an array holds, so the
+/// type of a path like `player.name` can be found from the Rust definition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LsonSchema {
+    /// Any Lson value, for fields that are Lson themselves.
+    Any,
+    Value(LsonType),
+    Optional(Box<LsonSchema>),
+    Array(Box<LsonSchema>),
+    Tuple(Vec<LsonSchema>),
+    /// A struct with any keys, all holding the same kind of value.
+    Map(Box<LsonSchema>),
+    Struct(Vec<(String, LsonSchema)>),
+    /// The variants of an enum, with the schema of what each one holds. Unit variants hold nothing.
+    Enum(Vec<(String, Option<LsonSchema>)>),
+}
+
+impl LsonSchema {
+    /// The type a value with this schema has in a script. Values that can take more than one type,
+    /// like an enum with both unit and data variants, have the type `None`.
+    pub fn lson_type(&self) -> LsonType {
+        match self {
+            LsonSchema::Any => LsonType::None,
+            LsonSchema::Value(lson_type) => *lson_type,
+            LsonSchema::Optional(inner) => inner.lson_type(),
+            LsonSchema::Array(_) | LsonSchema::Tuple(_) => LsonType::Array,
+            LsonSchema::Map(_) | LsonSchema::Struct(_) => LsonType::Struct,
+            LsonSchema::Enum(variants) => {
+                if variants.iter().all(|(_, inner)| inner.is_none()) {
+                    LsonType::String
+                } else if variants.iter().all(|(_, inner)| inner.is_some()) {
+                    LsonType::Struct
+                } else {
+                    LsonType::None
+                }
+            },
+        }
+    }
+
+    /// The schema of a field of a struct or map. Any value could have any field.
+    pub fn field(&self, name: &str) -> Option<&LsonSchema> {
+        match self {
+            LsonSchema::Any => Some(&LsonSchema::Any),
+            LsonSchema::Optional(inner) => inner.field(name),
+            LsonSchema::Map(inner) => Some(inner),
+            LsonSchema::Struct(fields) => fields.iter().find(|(field, _)| field == name).map(|(_, schema)| schema),
+            _ => None,
+        }
+    }
+
+    /// The schema at a dotted path of fields, like `player.name`.
+    pub fn path(&self, path: &str) -> Option<&LsonSchema> {
+        path.split('.').try_fold(self, |schema, name| schema.field(name))
+    }
+}
+
+//==================================================================================================
+//          Conversions
+//==================================================================================================
+
+/// A Rust value that can be handed to a script. Derive it with `#[derive(IntoLson)]`, which takes
+/// `#[lson(rename = "name")]` and `#[lson(skip)]` on fields and variants.
+pub trait IntoLson {
+    fn into_lson(self) -> Lson;
+
+    fn lson_schema() -> LsonSchema where Self: Sized;
+}
+
+/// A Rust value that can be read back out of a script. Derive it with `#[derive(FromLson)]`. Fields
+/// that are skipped are filled in with their `Default`.
+pub trait FromLson: Sized {
+    fn from_lson(value: Lson) -> Result<Self, LsonError>;
+
+    /// The value to use when a struct doesn't have the field at all. Only optional values have one.
+    fn from_missing() -> Option<Self> {
+        None
+    }
+}
+
+fn wrong_type(expected: LsonType, found: &Lson) -> LsonError {
+    LsonError::WrongType(expected.to_string(), found.get_type().to_string())
+}
+
+impl IntoLson for Lson {
+    fn into_lson(self) -> Lson {
+        self
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Any
+    }
+}
+
+impl FromLson for Lson {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        Ok(value)
+    }
+
+    fn from_missing() -> Option<Self> {
+        Some(Lson::None)
+    }
+}
+
+macro_rules! int_conversions {
+    ($($int:ty),*) => {$(
+        impl IntoLson for $int {
+            fn into_lson(self) -> Lson {
+                Lson::Int(self as i64)
+            }
+
+            fn lson_schema() -> LsonSchema {
+                LsonSchema::Value(LsonType::Int)
+            }
+        }
+
+        impl FromLson for $int {
+            fn from_lson(value: Lson) -> Result<Self, LsonError> {
+                match value {
+                    Lson::Int(int) => <$int>::try_from(int).map_err(|_| LsonError::IntOutOfRange(int.to_string())),
+                    other => Err(wrong_type(LsonType::Int, &other)),
+                }
+            }
+        }
+    )*};
+}
+
+int_conversions!(i8, i16, i32, i64, u8, u16, u32);
+
+impl IntoLson for f64 {
+    fn into_lson(self) -> Lson {
+        Lson::Float(self)
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Value(LsonType::Float)
+    }
+}
+
+impl FromLson for f64 {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::Float(float) => Ok(float),
+            Lson::Int(int) => Ok(int as f64),
+            other => Err(wrong_type(LsonType::Float, &other)),
+        }
+    }
+}
+
+impl IntoLson for f32 {
+    fn into_lson(self) -> Lson {
+        Lson::Float(self.into())
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Value(LsonType::Float)
+    }
+}
+
+impl FromLson for f32 {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        f64::from_lson(value).map(|float| float as f32)
+    }
+}
+
+impl IntoLson for bool {
+    fn into_lson(self) -> Lson {
+        Lson::Bool(self)
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Value(LsonType::Bool)
+    }
+}
+
+impl FromLson for bool {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::Bool(bool) => Ok(bool),
+            other => Err(wrong_type(LsonType::Bool, &other)),
+        }
+    }
+}
+
+impl IntoLson for String {
+    fn into_lson(self) -> Lson {
+        Lson::String(self)
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Value(LsonType::String)
+    }
+}
+
+impl FromLson for String {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::String(string) => Ok(string),
+            other => Err(wrong_type(LsonType::String, &other)),
+        }
+    }
+}
+
+impl IntoLson for &str {
+    fn into_lson(self) -> Lson {
+        Lson::String(self.to_string())
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Value(LsonType::String)
+    }
+}
+
+impl<T: IntoLson> IntoLson for Option<T> {
+    fn into_lson(self) -> Lson {
+        self.map_or(Lson::None, IntoLson::into_lson)
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Optional(Box::new(T::lson_schema()))
+    }
+}
+
+impl<T: FromLson> FromLson for Option<T> {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::None => Ok(None),
+            value => T::from_lson(value).map(Some),
+        }
+    }
+
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: IntoLson> IntoLson for Vec<T> {
+    fn into_lson(self) -> Lson {
+        Lson::Array(self.into_iter().map(IntoLson::into_lson).collect())
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Array(Box::new(T::lson_schema()))
+    }
+}
+
+impl<T: FromLson> FromLson for Vec<T> {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::Array(values) => values.into_iter().map(T::from_lson).collect(),
+            other => Err(wrong_type(LsonType::Array, &other)),
+        }
+    }
+}
+
+/// The fields are sorted by name, since a `HashMap` has no order of its own.
+impl<T: IntoLson> IntoLson for HashMap<String, T> {
+    fn into_lson(self) -> Lson {
+        let mut fields: Vec<(String, T)> = self.into_iter().collect();
+        fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Lson::Struct(fields.into_iter().map(|(key, value)| (key, value.into_lson())).collect())
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Map(Box::new(T::lson_schema()))
+    }
+}
+
+impl<T: FromLson> FromLson for HashMap<String, T> {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::Struct(fields) => fields.into_iter()
+                .map(|(key, value)| T::from_lson(value).map(|value| (key, value)))
+                .collect(),
+            other => Err(wrong_type(LsonType::Struct, &other)),
+        }
+    }
+}
+
+impl<T: IntoLson> IntoLson for IndexMap<String, T> {
+    fn into_lson(self) -> Lson {
+        Lson::Struct(self.into_iter().map(|(key, value)| (key, value.into_lson())).collect())
+    }
+
+    fn lson_schema() -> LsonSchema {
+        LsonSchema::Map(Box::new(T::lson_schema()))
+    }
+}
+
+impl<T: FromLson> FromLson for IndexMap<String, T> {
+    fn from_lson(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::Struct(fields) => fields.into_iter()
+                .map(|(key, value)| T::from_lson(value).map(|value| (key, value)))
+                .collect(),
+            other => Err(wrong_type(LsonType::Struct, &other)),
+        }
+    }
+}
+
+//==================================================================================================
+//          TryFrom
+//==================================================================================================
+
+macro_rules! try_from_lson {
+    ($($ty:ty),*) => {$(
+        impl TryFrom<Lson> for $ty {
+            type Error = LsonError;
+
+            fn try_from(value: Lson) -> Result<Self, LsonError> {
+                <$ty as FromLson>::from_lson(value)
+            }
+        }
+    )*};
+}
+
+try_from_lson!(i8, i16, i32, i64, u8, u16, u32, f32, f64, bool, String);
+
+impl<T: TryFrom<Lson, Error = LsonError>> TryFrom<Lson> for Vec<T> {
+    type Error = LsonError;
+
+    fn try_from(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::Array(values) => values.into_iter().enumerate()
+                .map(|(index, value)| T::try_from(value).map_err(|error| LsonError::InField(index.to_string(), Box::new(error))))
+                .collect(),
+            other => Err(wrong_type(LsonType::Array, &other)),
+        }
+    }
+}
+
+impl<T: TryFrom<Lson, Error = LsonError>> TryFrom<Lson> for HashMap<String, T> {
+    type Error = LsonError;
+
+    fn try_from(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::Struct(fields) => fields.into_iter()
+                .map(|(key, value)| match T::try_from(value) {
+                    Ok(value) => Ok((key, value)),
+                    Err(error) => Err(LsonError::InField(key, Box::new(error))),
+                })
+                .collect(),
+            other => Err(wrong_type(LsonType::Struct, &other)),
+        }
+    }
+}
+
+impl<T: TryFrom<Lson, Error = LsonError>> TryFrom<Lson> for IndexMap<String, T> {
+    type Error = LsonError;
+
+    fn try_from(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::Struct(fields) => fields.into_iter()
+                .map(|(key, value)| match T::try_from(value) {
+                    Ok(value) => Ok((key, value)),
+                    Err(error) => Err(LsonError::InField(key, Box::new(error))),
+                })
+                .collect(),
+            other => Err(wrong_type(LsonType::Struct, &other)),
+        }
+    }
+}
+
+/// `none` becomes `None`, and any other value has to convert to `T`.
+impl<T: TryFrom<Lson, Error = LsonError>> TryFrom<Lson> for Option<T> {
+    type Error = LsonError;
+
+    fn try_from(value: Lson) -> Result<Self, LsonError> {
+        match value {
+            Lson::None => Ok(None),
+            value => T::try_from(value).map(Some),
+        }
+    }
+}
+
+/// Reads a field out of a struct for `#[derive(FromLson)]`, naming the field in any error.
+#[doc(hidden)]
+pub fn take_field<T: FromLson>(fields: &mut LsonMap, name: &str) -> Result<T, LsonError> {
+    match fields.shift_remove(name) {
+        Some(value) => T::from_lson(value).map_err(|error| LsonError::InField(name.to_string(), Box::new(error))),
+        None => T::from_missing().ok_or_else(|| LsonError::MissingField(name.to_string())),
+    }
+}
+
+/// Takes the fields out of a struct for `#[derive(FromLson)]`.
+#[doc(hidden)]
+pub fn take_struct(value: Lson) -> Result<LsonMap, LsonError> {
+    match value {
+        Lson::Struct(fields) => Ok(fields),
+        other => Err(wrong_type(LsonType::Struct, &other)),
+    }
+}
+
+/// Takes the values out of an array of a known length for `#[derive(FromLson)]`.
+#[doc(hidden)]
+pub fn take_tuple(value: Lson, len: usize) -> Result<std::vec::IntoIter<Lson>, LsonError> {
+    match value {
+        Lson::Array(values) if values.len() == len => Ok(values.into_iter()),
+        Lson::Array(values) => Err(LsonError::WrongLength(len, values.len())),
+        other => Err(wrong_type(LsonType::Array, &other)),
+    }
+}
+
+/// Splits an enum value into its variant name and what it holds for `#[derive(FromLson)]`.
+#[doc(hidden)]
+pub fn take_variant(value: Lson) -> Result<(String, Lson), LsonError> {
+    match value {
+        Lson::String(variant) => Ok((variant, Lson::None)),
+        Lson::Struct(fields) if fields.len() == 1 => Ok(fields.into_iter().next().unwrap()),
+        other => Err(LsonError::NotAVariant(other.get_type().to_string())),
+    }
+}
+
+//==================================================================================================
+//          Conversion Tests
+//==================================================================================================
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::lson::{FromLson, IntoLson, Lson, LsonError, LsonSchema, LsonType};
+
+    #[derive(IntoLson, FromLson, Debug, PartialEq, Clone)]
+    enum Class {
+        Knight,
+        #[lson(rename = "wizard")]
+        Mage { school: String },
+        Rogue(u32),
+        Bard(String, u8),
+    }
+
+    #[derive(IntoLson, FromLson, Debug, PartialEq, Clone)]
+    struct Player {
+        #[lson(rename = "display_name")]
+        name: String,
+        level: u8,
+        health: f32,
+        pet: Option<String>,
+        classes: Vec<Class>,
+        #[lson(skip)]
+        session: u64,
+    }
+
+    #[derive(IntoLson, FromLson, Debug, PartialEq)]
+    struct Gold(i64);
+
+    #[derive(IntoLson, FromLson, Debug, PartialEq)]
+    struct Position(f64, f64);
+
+    fn player() -> Player {
+        Player {
+            name: "Ada".to_string(),
+            level: 3,
+            health: 7.5,
+            pet: None,
+            classes: vec![Class::Knight, Class::Mage { school: "fire".to_string() }, Class::Rogue(2), Class::Bard("lute".to_string(), 1)],
+            session: 99,
+        }
+    }
+
+    #[test]
+    fn into_lson() {
+        let value = player().into_lson();
+        assert_eq!(value["display_name"], "Ada");
+        assert_eq!(value["level"], 3);
+        assert_eq!(value["pet"], Lson::None);
+        assert_eq!(value["classes"][0], "Knight");
+        assert_eq!(value["classes"][1]["wizard"]["school"], "fire");
+        assert_eq!(value["classes"][2]["Rogue"], 2);
+        assert_eq!(value["classes"][3]["Bard"][0], "lute");
+        assert!(matches!(&value, Lson::Struct(fields) if !fields.contains_key("session") && !fields.contains_key("name")));
+
+        assert_eq!(Gold(5).into_lson(), Lson::Int(5));
+        assert_eq!(Position(1.0, 2.0).into_lson(), Lson::Array(vec![Lson::Float(1.0), Lson::Float(2.0)]));
+    }
+
+    #[test]
+    fn from_lson() {
+        let restored = Player::from_lson(player().into_lson()).unwrap();
+        assert_eq!(restored, Player { session: 0, ..player() });
+        assert_eq!(Gold::from_lson(Lson::Int(5)).unwrap(), Gold(5));
+        assert_eq!(Position::from_lson(Lson::Array(vec![Lson::Int(1), Lson::Float(2.0)])).unwrap(), Position(1.0, 2.0));
+
+        let mut value = player().into_lson();
+        if let Lson::Struct(fields) = &mut value {
+            fields.shift_remove("pet");
+        }
+        assert_eq!(Player::from_lson(value).unwrap().pet, None);
+
+        let mut value = player().into_lson();
+        if let Lson::Struct(fields) = &mut value {
+            fields.shift_remove("level");
+        }
+        assert_eq!(Player::from_lson(value), Err(LsonError::MissingField("level".to_string())));
+
+        let mut value = player().into_lson();
+        value["level"] = Lson::from("high");
+        assert_eq!(Player::from_lson(value), Err(LsonError::InField("level".to_string(), Box::new(LsonError::WrongType("int".to_string(), "string".to_string())))));
+
+        assert_eq!(Class::from_lson(Lson::from("Cleric")), Err(LsonError::UnknownVariant("Cleric".to_string())));
+        assert_eq!(Position::from_lson(Lson::Array(vec![Lson::Int(1)])), Err(LsonError::WrongLength(2, 1)));
+        assert_eq!(u8::from_lson(Lson::Int(300)), Err(LsonError::IntOutOfRange("300".to_string())));
+    }
+
+    #[test]
+    fn lson_schema() {
+        let schema = Player::lson_schema();
+        assert_eq!(schema.lson_type(), LsonType::Struct);
+        assert_eq!(schema.path("display_name"), Some(&LsonSchema::Value(LsonType::String)));
+        assert_eq!(schema.path("pet").map(LsonSchema::lson_type), Some(LsonType::String));
+        assert_eq!(schema.path("classes").map(LsonSchema::lson_type), Some(LsonType::Array));
+        assert_eq!(schema.path("session"), None);
+        assert_eq!(schema.path("display_name.first"), None);
+
+        assert_eq!(Class::lson_schema().lson_type(), LsonType::None);
+        assert_eq!(Gold::lson_schema(), LsonSchema::Value(LsonType::Int));
+        assert_eq!(HashMap::<String, Player>::lson_schema().path("ada.level"), Some(&LsonSchema::Value(LsonType::Int)));
+    }
+}
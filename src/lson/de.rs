@@ -0,0 +1,282 @@
+use std::fmt;
+
+use serde::{
+    de::{self, value::{MapDeserializer, SeqDeserializer}, DeserializeOwned, IntoDeserializer, Visitor},
+    forward_to_deserialize_any, Deserialize,
+};
+
+use super::{Lson, LsonError, LsonMap};
+
+//==================================================================================================
+//          Deserialize
+//==================================================================================================
+
+impl<'de> Deserialize<'de> for Lson {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(LsonVisitor)
+    }
+}
+
+struct LsonVisitor;
+
+impl<'de> Visitor<'de> for LsonVisitor {
+    type Value = Lson;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a value that Lson can hold")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Lson, E> {
+        Ok(Lson::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Lson, E> {
+        Ok(Lson::Int(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Lson, E> {
+        i64::try_from(value).map(Lson::Int).map_err(|_| E::custom(LsonError::IntOutOfRange(value.to_string())))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Lson, E> {
+        Ok(Lson::Float(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Lson, E> {
+        Ok(Lson::String(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Lson, E> {
+        Ok(Lson::String(value))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Lson, E> {
+        Ok(Lson::None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Lson, E> {
+        Ok(Lson::None)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Lson, D::Error> {
+        Lson::deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Lson, D::Error> {
+        Lson::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Lson, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Lson::Array(values))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Lson, A::Error> {
+        let mut fields = LsonMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry()? {
+            fields.insert(key, value);
+        }
+        Ok(Lson::Struct(fields))
+    }
+}
+
+/// Reads any deserializable value out of Lson, like the variables a script left behind. This is
+/// the reverse of [`to_value`](super::to_value), so values passed through a script come back as
+/// the same Rust types. Ints are accepted where floats are expected.
+pub fn from_value<T: DeserializeOwned>(value: Lson) -> Result<T, LsonError> {
+    T::deserialize(value)
+}
+
+//==================================================================================================
+//          Lson Deserializer
+//==================================================================================================
+
+impl<'de> de::Deserializer<'de> for Lson {
+    type Error = LsonError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LsonError> {
+        match self {
+            Lson::None => visitor.visit_unit(),
+            Lson::Int(value) => visitor.visit_i64(value),
+            Lson::Float(value) => visitor.visit_f64(value),
+            Lson::String(value) => visitor.visit_string(value),
+            Lson::Bool(value) => visitor.visit_bool(value),
+            Lson::Array(values) => {
+                let mut seq = SeqDeserializer::new(values.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            },
+            Lson::Struct(fields) => {
+                let mut map = MapDeserializer::new(fields.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            },
+            Lson::Function(..) => Err(LsonError::Function),
+        }
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LsonError> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LsonError> {
+        match self {
+            Lson::Int(value) => visitor.visit_f64(value as f64),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, LsonError> {
+        match self {
+            Lson::None => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, LsonError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, LsonError> {
+        match self {
+            Lson::String(variant) => visitor.visit_enum(VariantDeserializer { variant, value: None }),
+            Lson::Struct(fields) if fields.len() == 1 => {
+                let (variant, value) = fields.into_iter().next().unwrap();
+                visitor.visit_enum(VariantDeserializer { variant, value: Some(value) })
+            },
+            value => Err(LsonError::NotAVariant(value.get_type().to_string())),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, LsonError> for Lson {
+    type Deserializer = Lson;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// An enum variant read from Lson: either the name of a unit variant, or a struct holding a single
+/// field named after the variant.
+struct VariantDeserializer {
+    variant: String,
+    value: Option<Lson>,
+}
+
+impl<'de> de::EnumAccess<'de> for VariantDeserializer {
+    type Error = LsonError;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), LsonError> {
+        let variant = seed.deserialize(IntoDeserializer::<LsonError>::into_deserializer(self.variant.as_str()))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = LsonError;
+
+    fn unit_variant(self) -> Result<(), LsonError> {
+        match self.value {
+            None | Some(Lson::None) => Ok(()),
+            Some(value) => Err(LsonError::NotAVariant(value.get_type().to_string())),
+        }
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, LsonError> {
+        seed.deserialize(self.value.unwrap_or(Lson::None))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, LsonError> {
+        de::Deserializer::deserialize_seq(self.value.unwrap_or(Lson::None), visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, LsonError> {
+        de::Deserializer::deserialize_map(self.value.unwrap_or(Lson::None), visitor)
+    }
+}
+
+//==================================================================================================
+//          Deserialize Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::lson::{from_value, to_value, Lson, LsonError, LsonType};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Class {
+        Knight,
+        Mage { school: String },
+        Rogue(u32),
+        Bard(String, u8),
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Hero {
+        name: String,
+        level: u8,
+        health: f32,
+        pet: Option<String>,
+        classes: Vec<Class>,
+    }
+
+    #[test]
+    fn deserialize_from_value() {
+        let hero = Hero {
+            name: "Ada".to_string(),
+            level: 3,
+            health: 7.5,
+            pet: Some("Rook".to_string()),
+            classes: vec![Class::Knight, Class::Mage { school: "fire".to_string() }, Class::Rogue(2), Class::Bard("lute".to_string(), 1)],
+        };
+        let value = to_value(&hero).unwrap();
+        assert_eq!(from_value::<Hero>(value).unwrap(), hero);
+
+        let value = Lson::from(HashMap::from([
+            ("name", Lson::from("Bo")),
+            ("level", Lson::Int(1)),
+            ("health", Lson::Int(10)),
+            ("pet", Lson::None),
+            ("classes", Lson::Array(vec![])),
+        ]));
+        let hero = from_value::<Hero>(value).unwrap();
+        assert_eq!(hero.health, 10.0);
+        assert_eq!(hero.pet, None);
+
+        assert!(matches!(from_value::<u8>(Lson::Int(300)), Err(LsonError::Custom(_))));
+        assert!(matches!(from_value::<Hero>(Lson::from("Ada")), Err(LsonError::Custom(_))));
+        assert_eq!(from_value::<Class>(Lson::Int(1)), Err(LsonError::NotAVariant("int".to_string())));
+    }
+
+    #[test]
+    fn deserialize_lson() {
+        let value = Lson::from(HashMap::from([
+            ("gold", Lson::Int(12)),
+            ("names", Lson::from(["a", "b"])),
+            ("flag", Lson::Bool(true)),
+            ("nothing", Lson::None),
+            ("ratio", Lson::Float(0.25)),
+        ]));
+        assert_eq!(from_value::<Lson>(value.clone()).unwrap(), value);
+        assert_eq!(to_value(&value).unwrap(), value);
+
+        let function = Lson::Function(Arc::new(|_, _| Lson::None), LsonType::None);
+        assert_eq!(from_value::<Lson>(function), Err(LsonError::Function));
+    }
+}
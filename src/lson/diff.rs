@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Lson, LsonMap, LsonPath, LsonPathError, LsonPathSegment};
+
+//==================================================================================================
+//          Lson Patch
+//==================================================================================================
+
+/// A single change to a value, addressed by the path of what it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LsonChange {
+    /// Replaces the value at the path, or adds it if it's a new field of a struct.
+    Set {
+        path: LsonPath,
+        #[serde(with = "crate::lson::tagged")]
+        value: Lson,
+    },
+    /// Inserts a value into an array, moving the values after it back by one.
+    Insert {
+        path: LsonPath,
+        #[serde(with = "crate::lson::tagged")]
+        value: Lson,
+    },
+    /// Removes a field of a struct, or a value of an array, moving the values after it up by one.
+    Remove { path: LsonPath },
+}
+
+impl LsonChange {
+    pub fn path(&self) -> &LsonPath {
+        match self {
+            LsonChange::Set { path, .. } | LsonChange::Insert { path, .. } | LsonChange::Remove { path } => path,
+        }
+    }
+}
+
+/// The changes that turn one value into another, made by [`Lson::diff`]. Changes are applied in
+/// order, so the index of a change to an array already accounts for the values inserted or
+/// removed before it.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LsonPatch {
+    changes: Vec<LsonChange>,
+}
+
+impl LsonPatch {
+    pub fn changes(&self) -> &[LsonChange] {
+        &self.changes
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, LsonChange> {
+        self.changes.iter()
+    }
+}
+
+impl From<Vec<LsonChange>> for LsonPatch {
+    fn from(changes: Vec<LsonChange>) -> Self {
+        LsonPatch { changes }
+    }
+}
+
+impl IntoIterator for LsonPatch {
+    type Item = LsonChange;
+    type IntoIter = std::vec::IntoIter<LsonChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a LsonPatch {
+    type Item = &'a LsonChange;
+    type IntoIter = std::slice::Iter<'a, LsonChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+//==================================================================================================
+//          Diff
+//==================================================================================================
+
+impl Lson {
+    /// Finds the changes that turn `from` into `to`, down to the order of struct fields, since the
+    /// order shows when a value is printed. Fields that are already in the order of `to` are
+    /// diffed in place, and the rest are removed and set again at the end, in the order `to` has
+    /// them. An int and a float of the same number count as different, since a script can tell
+    /// them apart.
+    pub fn diff(from: &Lson, to: &Lson) -> LsonPatch {
+        let mut changes = Vec::new();
+        diff_into(&mut changes, &mut LsonPath::new(), from, to);
+        LsonPatch { changes }
+    }
+
+    /// Applies the changes of a patch in order. Either every change applies, or the value is left
+    /// as it was and the error names the path that couldn't be followed.
+    pub fn apply(&mut self, patch: &LsonPatch) -> Result<(), LsonPathError> {
+        let mut value = self.clone();
+        for change in patch {
+            value.apply_change(change)?;
+        }
+        *self = value;
+        Ok(())
+    }
+
+    fn apply_change(&mut self, change: &LsonChange) -> Result<(), LsonPathError> {
+        let path = change.path();
+        let mut parent_path = path.clone();
+        let Some(last) = parent_path.pop() else {
+            return match change {
+                LsonChange::Set { value, .. } => {
+                    *self = value.clone();
+                    Ok(())
+                },
+                _ => Err(LsonPathError::WholeValue),
+            };
+        };
+        let parent = self.lookup_mut(&parent_path)?;
+        let position = parent_path.segments().len();
+
+        match (change, &last, &mut *parent) {
+            (LsonChange::Set { value, .. } | LsonChange::Insert { value, .. }, LsonPathSegment::Field(name), Lson::Struct(fields)) => {
+                fields.insert(name.clone(), value.clone());
+            },
+            (LsonChange::Set { value, .. }, LsonPathSegment::Index(index), Lson::Array(values)) if *index < values.len() => {
+                values[*index] = value.clone();
+            },
+            (LsonChange::Insert { value, .. }, LsonPathSegment::Index(index), Lson::Array(values)) if *index <= values.len() => {
+                values.insert(*index, value.clone());
+            },
+            (LsonChange::Remove { .. }, LsonPathSegment::Field(name), Lson::Struct(fields)) if fields.contains_key(name) => {
+                fields.shift_remove(name);
+            },
+            (LsonChange::Remove { .. }, LsonPathSegment::Index(index), Lson::Array(values)) if *index < values.len() => {
+                values.remove(*index);
+            },
+            _ => return Err(path.failure(position, parent)),
+        }
+        Ok(())
+    }
+}
+
+fn diff_into(changes: &mut Vec<LsonChange>, path: &mut LsonPath, from: &Lson, to: &Lson) {
+    match (from, to) {
+        (Lson::Struct(from_fields), Lson::Struct(to_fields)) => diff_structs(changes, path, from_fields, to_fields),
+        (Lson::Array(from_values), Lson::Array(to_values)) => diff_arrays(changes, path, from_values, to_values),
+        (from, to) if !identical(from, to) => changes.push(LsonChange::Set { path: path.clone(), value: to.clone() }),
+        _ => {},
+    }
+}
+
+/// Setting a field that a struct doesn't have adds it at the end, so the fields that stay where they
+/// are have to be the start of `to`, in the order `from` already has them. Every other field of
+/// `to` is removed if it's there, and set again after them.
+fn diff_structs(changes: &mut Vec<LsonChange>, path: &mut LsonPath, from: &LsonMap, to: &LsonMap) {
+    let mut kept = from.keys().filter(|name| to.contains_key(*name));
+    let in_place = to.keys().take_while(|name| kept.any(|kept| kept == *name)).count();
+
+    for (name, from_value) in from {
+        path.push(LsonPathSegment::Field(name.clone()));
+        match to.get_index_of(name) {
+            Some(index) if index < in_place => diff_into(changes, path, from_value, &to[index]),
+            Some(_) => {},
+            None => changes.push(LsonChange::Remove { path: path.clone() }),
+        }
+        path.pop();
+    }
+    for (name, to_value) in to.iter().skip(in_place) {
+        path.push(LsonPathSegment::Field(name.clone()));
+        if from.contains_key(name) {
+            changes.push(LsonChange::Remove { path: path.clone() });
+        }
+        changes.push(LsonChange::Set { path: path.clone(), value: to_value.clone() });
+        path.pop();
+    }
+}
+
+/// Values shared at the start and end of both arrays are left alone. What's between them is
+/// compared position by position, and the difference in length is made up by removing or inserting
+/// values at the end of it, which keeps a single insertion or removal to a single change.
+fn diff_arrays(changes: &mut Vec<LsonChange>, path: &mut LsonPath, from: &[Lson], to: &[Lson]) {
+    let prefix = from.iter().zip(to).take_while(|(from, to)| identical(from, to)).count();
+    let suffix = from[prefix..].iter().rev().zip(to[prefix..].iter().rev()).take_while(|(from, to)| identical(from, to)).count();
+    let from_middle = &from[prefix..from.len() - suffix];
+    let to_middle = &to[prefix..to.len() - suffix];
+    let shared = from_middle.len().min(to_middle.len());
+
+    for index in 0..shared {
+        path.push(LsonPathSegment::Index(prefix + index));
+        diff_into(changes, path, &from_middle[index], &to_middle[index]);
+        path.pop();
+    }
+    let end = path.join(LsonPathSegment::Index(prefix + shared));
+    for _ in shared..from_middle.len() {
+        changes.push(LsonChange::Remove { path: end.clone() });
+    }
+    for (offset, value) in to_middle[shared..].iter().enumerate() {
+        changes.push(LsonChange::Insert { path: path.join(LsonPathSegment::Index(prefix + shared + offset)), value: value.clone() });
+    }
+}
+
+/// Equality that tells ints from floats and minds the order of fields, where `==` on Lson doesn't.
+fn identical(left: &Lson, right: &Lson) -> bool {
+    match (left, right) {
+        (Lson::Float(left), Lson::Float(right)) => left.to_bits() == right.to_bits() || left == right,
+        (Lson::Array(left), Lson::Array(right)) => left.len() == right.len() && left.iter().zip(right).all(|(l, r)| identical(l, r)),
+        (Lson::Struct(left), Lson::Struct(right)) => identical_fields(left, right),
+        (Lson::Function(left, _), Lson::Function(right, _)) => Arc::ptr_eq(left, right),
+        (left, right) => left.get_type() == right.get_type() && left == right,
+    }
+}
+
+fn identical_fields(left: &LsonMap, right: &LsonMap) -> bool {
+    left.len() == right.len() && left.iter().zip(right).all(|((left_name, left), (right_name, right))| left_name == right_name && identical(left, right))
+}
+
+//==================================================================================================
+//          Lson Diff Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::lson::{Lson, LsonChange, LsonPatch, LsonPathError};
+
+    #[test]
+    fn diff_structs() {
+        let from = crate::lson!({ name : "Ada", gold : 10, pet : "Rook", stats : { hp : 3 } });
+        let patch = Lson::diff(&from, &crate::lson!({ name : "Ada", gold : 12, stats : { hp : 3, mp : 1 } }));
+        assert_eq!(patch.changes(), &[
+            LsonChange::Set { path: "gold".parse().unwrap(), value: Lson::Int(12) },
+            LsonChange::Remove { path: "pet".parse().unwrap() },
+            LsonChange::Set { path: "stats.mp".parse().unwrap(), value: Lson::Int(1) },
+        ]);
+        assert!(Lson::diff(&crate::lson!({ a : [1, 2] }), &crate::lson!({ a : [1, 2] })).is_empty());
+        assert_eq!(Lson::diff(&Lson::Int(1), &Lson::Float(1.0)).changes(), &[LsonChange::Set { path: "".parse().unwrap(), value: Lson::Float(1.0) }]);
+
+        let patch = Lson::diff(&crate::lson!({ a : 1, b : 2 }), &crate::lson!({ b : 2, a : 1 }));
+        assert_eq!(patch.changes(), &[
+            LsonChange::Remove { path: "a".parse().unwrap() },
+            LsonChange::Set { path: "a".parse().unwrap(), value: Lson::Int(1) },
+        ]);
+
+        let from = crate::lson!({ name : "Ada", gold : 10, old : true, stats : { hp : 3, mp : 1 } });
+        let patch = Lson::diff(&from, &crate::lson!({ name : "Ada", new : 1, gold : 12, stats : { mp : 1, hp : 4 } }));
+        assert_eq!(patch.changes(), &[
+            LsonChange::Remove { path: "old".parse().unwrap() },
+            LsonChange::Set { path: "new".parse().unwrap(), value: Lson::Int(1) },
+            LsonChange::Remove { path: "gold".parse().unwrap() },
+            LsonChange::Set { path: "gold".parse().unwrap(), value: Lson::Int(12) },
+            LsonChange::Remove { path: "stats".parse().unwrap() },
+            LsonChange::Set { path: "stats".parse().unwrap(), value: crate::lson!({ mp : 1, hp : 4 }) },
+        ]);
+    }
+
+    #[test]
+    fn diff_arrays() {
+        let patch = Lson::diff(&crate::lson!([1, 2, 3, 4]), &crate::lson!([1, 2, 9, 3, 4]));
+        assert_eq!(patch.changes(), &[LsonChange::Insert { path: "[2]".parse().unwrap(), value: Lson::Int(9) }]);
+
+        let patch = Lson::diff(&crate::lson!([1, 2, 3, 4]), &crate::lson!([1, 4]));
+        assert_eq!(patch.changes(), &[
+            LsonChange::Remove { path: "[1]".parse().unwrap() },
+            LsonChange::Remove { path: "[1]".parse().unwrap() },
+        ]);
+    }
+
+    #[test]
+    fn apply_patch() {
+        let pairs = [
+            (crate::lson!({ name : "Ada", gold : 10, pet : "Rook" }), crate::lson!({ gold : 12, name : "Ada", stats : { hp : 3 } })),
+            (crate::lson!([1, 2, 3, 4]), crate::lson!([1, 2, 9, 3, 4])),
+            (crate::lson!([{ a : 1, b : 2 }, 3]), crate::lson!([{ b : 2, a : 1 }, 3.0])),
+            (crate::lson!({ party : [{ name : "Ada" }, { name : "Bo" }] }), crate::lson!({ party : [{ name : "Ada", level : 2 }, { name : "Cy" }, { name : "Di" }] })),
+            (crate::lson!([1, 2]), crate::lson!({ now : "a struct" })),
+        ];
+        for (from, to) in pairs {
+            let mut patched = from.clone();
+            patched.apply(&Lson::diff(&from, &to)).unwrap();
+            assert_eq!(patched.to_string(), to.to_string());
+        }
+
+        let mut value = crate::lson!({ list : [1, 2] });
+        let patch = LsonPatch::from(vec![
+            LsonChange::Set { path: "list[0]".parse().unwrap(), value: Lson::Int(5) },
+            LsonChange::Remove { path: "list[4]".parse().unwrap() },
+        ]);
+        assert_eq!(value.apply(&patch), Err(LsonPathError::IndexOutOfBounds("list[4]".to_string(), 4, 2)));
+        assert_eq!(value, crate::lson!({ list : [1, 2] }));
+
+        let patch = LsonPatch::from(vec![LsonChange::Set { path: "missing.field".parse().unwrap(), value: Lson::None }]);
+        assert_eq!(value.apply(&patch), Err(LsonPathError::MissingField("missing".to_string(), "missing".to_string())));
+    }
+
+    #[test]
+    fn patch_serde() {
+        let patch = Lson::diff(&crate::lson!({ a : [1], b : "x" }), &crate::lson!({ a : [1, 2.5] }));
+        let bytes = bincode::serialize(&patch).unwrap();
+        assert_eq!(bincode::deserialize::<LsonPatch>(&bytes).unwrap(), patch);
+
+        let value = crate::lson::to_value(&patch).unwrap();
+        assert_eq!(value[0]["Insert"]["path"], "a[1]");
+    }
+}
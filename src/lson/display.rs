@@ -0,0 +1,218 @@
+use std::fmt::{self, Display, Write};
+
+use super::Lson;
+
+//==================================================================================================
+//          Display
+//==================================================================================================
+
+/// Writes the value the way it would be written in a script, so `value.to_string().parse()` gives
+/// the value back. The plain form fits on one line, and the alternate form, `{:#}`, is the same as
+/// [`Lson::pretty`] with four spaces of indentation.
+///
+/// Functions, and floats that aren't finite, have no literal, and are written in a form that
+/// reads well in a log but can't be parsed.
+impl Display for Lson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = if f.alternate() { Some(4) } else { None };
+        write_value(f, self, indent, 0)
+    }
+}
+
+/// A value being written with a chosen layout, made by [`Lson::pretty`] and [`Lson::compact`].
+pub struct LsonDisplay<'l> {
+    value: &'l Lson,
+    indent: Option<usize>,
+}
+
+impl Display for LsonDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self.value, self.indent, 0)
+    }
+}
+
+impl Lson {
+    /// Writes the value over several lines, with each field of a struct and each value of an
+    /// array on its own line, indented by `indent` spaces for each level it is nested.
+    pub fn pretty(&self, indent: usize) -> LsonDisplay<'_> {
+        LsonDisplay { value: self, indent: Some(indent) }
+    }
+
+    /// Writes the value on a single line. This is the same as the plain `Display`.
+    pub fn compact(&self) -> LsonDisplay<'_> {
+        LsonDisplay { value: self, indent: None }
+    }
+}
+
+fn write_value(f: &mut fmt::Formatter<'_>, value: &Lson, indent: Option<usize>, depth: usize) -> fmt::Result {
+    match value {
+        Lson::None => f.write_str("none"),
+        Lson::Int(value) => write!(f, "{}", value),
+        Lson::Float(value) => write_float(f, *value),
+        Lson::String(value) => write_string(f, value),
+        Lson::Bool(value) => write!(f, "{}", value),
+        Lson::Array(values) if values.is_empty() => f.write_str("[]"),
+        Lson::Array(values) => {
+            f.write_char('[')?;
+            for (position, value) in values.iter().enumerate() {
+                write_separator(f, position, indent, depth + 1)?;
+                write_value(f, value, indent, depth + 1)?;
+            }
+            write_closing(f, ']', indent, depth)
+        },
+        Lson::Struct(fields) if fields.is_empty() => f.write_str("{}"),
+        Lson::Struct(fields) => {
+            f.write_char('{')?;
+            for (position, (key, value)) in fields.iter().enumerate() {
+                write_separator(f, position, indent, depth + 1)?;
+                if indent.is_none() && position == 0 {
+                    f.write_char(' ')?;
+                }
+                write_key(f, key)?;
+                f.write_str(" : ")?;
+                write_value(f, value, indent, depth + 1)?;
+            }
+            match indent {
+                Some(_) => write_closing(f, '}', indent, depth),
+                None => f.write_str(" }"),
+            }
+        },
+        Lson::Function(_, returns) => write!(f, "function() -> {}", returns.to_string()),
+    }
+}
+
+/// Starts a value inside of an array or struct: on its own line when pretty, and after a comma
+/// when it isn't the first.
+fn write_separator(f: &mut fmt::Formatter<'_>, position: usize, indent: Option<usize>, depth: usize) -> fmt::Result {
+    if position > 0 {
+        f.write_char(',')?;
+    }
+    match indent {
+        Some(indent) => write!(f, "\n{:width$}", "", width = indent * depth),
+        None if position > 0 => f.write_char(' '),
+        None => Ok(()),
+    }
+}
+
+fn write_closing(f: &mut fmt::Formatter<'_>, bracket: char, indent: Option<usize>, depth: usize) -> fmt::Result {
+    if let Some(indent) = indent {
+        write!(f, "\n{:width$}", "", width = indent * depth)?;
+    }
+    f.write_char(bracket)
+}
+
+/// Floats always have a decimal point, so they read back as floats rather than ints.
+fn write_float(f: &mut fmt::Formatter<'_>, value: f64) -> fmt::Result {
+    if !value.is_finite() {
+        return write!(f, "{}", value);
+    }
+    let text = value.to_string();
+    f.write_str(&text)?;
+    if !text.contains('.') {
+        f.write_str(".0")?;
+    }
+    Ok(())
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Words the logic language reads as something other than a name, which have to be quoted to be
+/// used as a key.
+const RESERVED: &[&str] = &[
+    "true", "false", "none", "float", "int", "string", "bool", "struct", "array", "function",
+    "if", "else", "for", "in", "let", "const", "yield", "wait", "request",
+];
+
+/// Keys are written as names where they can be, and as strings otherwise.
+fn write_key(f: &mut fmt::Formatter<'_>, key: &str) -> fmt::Result {
+    let is_name = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !RESERVED.contains(&key);
+    if is_name {
+        f.write_str(key)
+    } else {
+        write_string(f, key)
+    }
+}
+
+//==================================================================================================
+//          Display Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::lson::Lson;
+
+    fn hero() -> Lson {
+        crate::lson!({
+            name : "Ada",
+            level : 3,
+            health : 7.0,
+            pet : none,
+            items : ["sword", { kind : "potion", heals : -2.5 }],
+            "max hp" : 12,
+            empty : [],
+            nothing : {},
+        })
+    }
+
+    #[test]
+    fn display_compact() {
+        assert_eq!(Lson::Int(-4).to_string(), "-4");
+        assert_eq!(Lson::Float(2.0).to_string(), "2.0");
+        assert_eq!(Lson::Float(1e21).to_string(), "1000000000000000000000.0");
+        assert_eq!(Lson::from("say \"hi\"\n").to_string(), r#""say \"hi\"\n""#);
+        assert_eq!(
+            hero().to_string(),
+            r#"{ name : "Ada", level : 3, health : 7.0, pet : none, items : ["sword", { kind : "potion", heals : -2.5 }], "max hp" : 12, empty : [], nothing : {} }"#,
+        );
+        assert_eq!(hero().compact().to_string(), hero().to_string());
+        assert_eq!(crate::lson!({ "if" : 1, "2nd" : 2 }).to_string(), r#"{ "if" : 1, "2nd" : 2 }"#);
+    }
+
+    #[test]
+    fn display_pretty() {
+        let expected = r#"{
+    name : "Ada",
+    level : 3,
+    health : 7.0,
+    pet : none,
+    items : [
+        "sword",
+        {
+            kind : "potion",
+            heals : -2.5
+        }
+    ],
+    "max hp" : 12,
+    empty : [],
+    nothing : {}
+}"#;
+        assert_eq!(format!("{:#}", hero()), expected);
+        assert_eq!(hero().pretty(2).to_string().lines().nth(7), Some("    {"));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let value = hero();
+        assert_eq!(value.to_string().parse::<Lson>().unwrap(), value);
+        assert_eq!(format!("{:#}", value).parse::<Lson>().unwrap(), value);
+        assert_eq!(Lson::Float(-0.5).to_string().parse::<Lson>().unwrap(), Lson::Float(-0.5));
+        assert!(matches!(Lson::Float(3.0).to_string().parse::<Lson>(), Ok(Lson::Float(_))));
+        let escaped = Lson::from("a \"quote\",\ta \\ and\r\n a line");
+        assert_eq!(escaped.to_string().parse::<Lson>().unwrap(), escaped);
+    }
+}
@@ -0,0 +1,432 @@
+use std::fmt::Write;
+
+use super::{Lson, LsonError, LsonMap};
+
+/// How deeply arrays and structs may be nested in JSON that is read, so that a hostile file can't
+/// overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+//==================================================================================================
+//          JSON
+//==================================================================================================
+
+impl Lson {
+    /// Reads a value out of JSON. `null` becomes `none`, a number written without a fraction or
+    /// an exponent becomes an int, and any other number becomes a float, so `2` and `2.0` stay
+    /// apart. An int too large for 64 bits is an error rather than a float that lost precision.
+    /// Struct fields keep the order of the file, and a repeated key keeps its first position and
+    /// its last value.
+    pub fn from_json_str(source: &str) -> Result<Lson, LsonError> {
+        let mut reader = JsonReader { source, position: 0, depth: 0 };
+        reader.skip_whitespace();
+        let value = reader.read_value()?;
+        reader.skip_whitespace();
+        if reader.position < source.len() {
+            return Err(reader.error("expected the end of the file"));
+        }
+        Ok(value)
+    }
+
+    /// Writes the value as JSON on a single line. `none` is written as `null`, and floats are
+    /// always written with a fraction, so they read back as floats. Functions, and floats that
+    /// aren't finite, have no JSON form and are errors.
+    pub fn to_json_string(&self) -> Result<String, LsonError> {
+        let mut output = String::new();
+        write_json(&mut output, self, None, 0)?;
+        Ok(output)
+    }
+
+    /// Writes the value as JSON, with each field and array value on its own line, indented by
+    /// `indent` spaces for each level it is nested.
+    pub fn to_json_string_pretty(&self, indent: usize) -> Result<String, LsonError> {
+        let mut output = String::new();
+        write_json(&mut output, self, Some(indent), 0)?;
+        Ok(output)
+    }
+}
+
+//==================================================================================================
+//          Reading
+//==================================================================================================
+
+struct JsonReader<'s> {
+    source: &'s str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'s> JsonReader<'s> {
+    fn error(&self, message: &str) -> LsonError {
+        let before = &self.source[..self.position];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        LsonError::Json(line, column, message.to_string())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, message: &str) -> Result<(), LsonError> {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn read_word(&mut self, word: &str, value: Lson) -> Result<Lson, LsonError> {
+        if self.source[self.position..].starts_with(word) {
+            self.position += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("expected a value"))
+        }
+    }
+
+    fn read_value(&mut self) -> Result<Lson, LsonError> {
+        match self.peek() {
+            Some(b'n') => self.read_word("null", Lson::None),
+            Some(b't') => self.read_word("true", Lson::Bool(true)),
+            Some(b'f') => self.read_word("false", Lson::Bool(false)),
+            Some(b'"') => self.read_string().map(Lson::String),
+            Some(b'-' | b'0'..=b'9') => self.read_number(),
+            Some(b'[') => self.nested(Self::read_array),
+            Some(b'{') => self.nested(Self::read_object),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("expected a value, but the file ended")),
+        }
+    }
+
+    fn nested(&mut self, read: fn(&mut Self) -> Result<Lson, LsonError>) -> Result<Lson, LsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("the values are nested too deeply"));
+        }
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn read_array(&mut self) -> Result<Lson, LsonError> {
+        self.position += 1;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Lson::Array(values));
+        }
+        loop {
+            self.skip_whitespace();
+            values.push(self.read_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Lson::Array(values));
+                },
+                _ => return Err(self.error("expected a ',' or a ']'")),
+            }
+        }
+    }
+
+    fn read_object(&mut self) -> Result<Lson, LsonError> {
+        self.position += 1;
+        let mut fields = LsonMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Lson::Struct(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a key"));
+            }
+            let key = self.read_string()?;
+            self.skip_whitespace();
+            self.expect(b':', "expected a ':'")?;
+            self.skip_whitespace();
+            let value = self.read_value()?;
+            fields.insert(key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Lson::Struct(fields));
+                },
+                _ => return Err(self.error("expected a ',' or a '}'")),
+            }
+        }
+    }
+
+    fn read_number(&mut self) -> Result<Lson, LsonError> {
+        let start = self.position;
+        let mut is_float = false;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.position += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.error("expected a digit")),
+        }
+        if self.peek() == Some(b'.') {
+            is_float = true;
+            self.position += 1;
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error("expected a digit after the '.'"));
+            }
+            self.skip_digits();
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            is_float = true;
+            self.position += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.position += 1;
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error("expected a digit in the exponent"));
+            }
+            self.skip_digits();
+        }
+
+        let text = &self.source[start..self.position];
+        if is_float {
+            // Every number that matches the JSON grammar is a valid f64, though it may round.
+            Ok(Lson::Float(text.parse().unwrap()))
+        } else {
+            text.parse().map(Lson::Int).map_err(|_| LsonError::IntOutOfRange(text.to_string()))
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String, LsonError> {
+        self.position += 1;
+        let mut value = String::new();
+        loop {
+            let rest = &self.source[self.position..];
+            let Some(c) = rest.chars().next() else {
+                return Err(self.error("the string is never closed"));
+            };
+            match c {
+                '"' => {
+                    self.position += 1;
+                    return Ok(value);
+                },
+                '\\' => {
+                    self.position += 1;
+                    value.push(self.read_escape()?);
+                },
+                c if (c as u32) < 0x20 => return Err(self.error("a control character in a string must be escaped")),
+                c => {
+                    value.push(c);
+                    self.position += c.len_utf8();
+                },
+            }
+        }
+    }
+
+    fn read_escape(&mut self) -> Result<char, LsonError> {
+        let escaped = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.position += 1;
+                let high = self.read_hex()?;
+                if !(0xD800..0xDC00).contains(&high) {
+                    return char::from_u32(high).ok_or_else(|| self.error("the escape is not a character"));
+                }
+                // A character outside of the basic plane is written as a pair of escapes.
+                if !self.source[self.position..].starts_with("\\u") {
+                    return Err(self.error("expected the second half of a surrogate pair"));
+                }
+                self.position += 2;
+                let low = self.read_hex()?;
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(self.error("expected the second half of a surrogate pair"));
+                }
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                return char::from_u32(code).ok_or_else(|| self.error("the escape is not a character"));
+            },
+            _ => return Err(self.error("unknown escape")),
+        };
+        self.position += 1;
+        Ok(escaped)
+    }
+
+    fn read_hex(&mut self) -> Result<u32, LsonError> {
+        let digits = self.source.get(self.position..self.position + 4)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("expected four hex digits"))?;
+        self.position += 4;
+        Ok(u32::from_str_radix(digits, 16).unwrap())
+    }
+}
+
+//==================================================================================================
+//          Writing
+//==================================================================================================
+
+fn write_json(output: &mut String, value: &Lson, indent: Option<usize>, depth: usize) -> Result<(), LsonError> {
+    match value {
+        Lson::None => output.push_str("null"),
+        Lson::Int(value) => write!(output, "{}", value).unwrap(),
+        Lson::Float(value) if !value.is_finite() => return Err(LsonError::NotFinite(value.to_string())),
+        Lson::Float(value) => {
+            let text = format!("{:?}", value);
+            output.push_str(&text);
+            if !text.contains(['.', 'e', 'E']) {
+                output.push_str(".0");
+            }
+        },
+        Lson::String(value) => write_json_string(output, value),
+        Lson::Bool(value) => write!(output, "{}", value).unwrap(),
+        Lson::Array(values) if values.is_empty() => output.push_str("[]"),
+        Lson::Array(values) => {
+            output.push('[');
+            for (position, value) in values.iter().enumerate() {
+                write_json_separator(output, position, indent, depth + 1);
+                write_json(output, value, indent, depth + 1)?;
+            }
+            write_json_closing(output, ']', indent, depth);
+        },
+        Lson::Struct(fields) if fields.is_empty() => output.push_str("{}"),
+        Lson::Struct(fields) => {
+            output.push('{');
+            for (position, (key, value)) in fields.iter().enumerate() {
+                write_json_separator(output, position, indent, depth + 1);
+                write_json_string(output, key);
+                output.push_str(if indent.is_some() { ": " } else { ":" });
+                write_json(output, value, indent, depth + 1)?;
+            }
+            write_json_closing(output, '}', indent, depth);
+        },
+        Lson::Function(..) => return Err(LsonError::Function),
+    }
+    Ok(())
+}
+
+fn write_json_separator(output: &mut String, position: usize, indent: Option<usize>, depth: usize) {
+    if position > 0 {
+        output.push(',');
+    }
+    if let Some(indent) = indent {
+        output.push('\n');
+        output.extend(std::iter::repeat_n(' ', indent * depth));
+    }
+}
+
+fn write_json_closing(output: &mut String, bracket: char, indent: Option<usize>, depth: usize) {
+    if let Some(indent) = indent {
+        output.push('\n');
+        output.extend(std::iter::repeat_n(' ', indent * depth));
+    }
+    output.push(bracket);
+}
+
+fn write_json_string(output: &mut String, value: &str) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{c}' => output.push_str("\\f"),
+            c if (c as u32) < 0x20 => write!(output, "\\u{:04x}", c as u32).unwrap(),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+//==================================================================================================
+//          JSON Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use crate::{lson::{FromLson, Lson, LsonError, LsonType}, runtime::LibrettoRuntime};
+
+    const SAVE: &str = r#"{
+        "player": { "name": "Ada \"the\" Bold", "gold": 120, "health": 7.5, "ratio": 2.0 },
+        "flags": [true, false, null],
+        "log": "line one\nline two é 🎵",
+        "big": 1e3,
+        "empty": {}
+    }"#;
+
+    #[test]
+    fn read_json() {
+        let value = Lson::from_json_str(SAVE).unwrap();
+        assert_eq!(value["player"]["name"], "Ada \"the\" Bold");
+        assert!(matches!(value["player"]["gold"], Lson::Int(120)));
+        assert!(matches!(value["player"]["ratio"], Lson::Float(_)));
+        assert!(matches!(value["big"], Lson::Float(_)));
+        assert_eq!(value["flags"], crate::lson!([true, false, none]));
+        assert_eq!(value["log"], "line one\nline two \u{e9} \u{1f3b5}");
+
+        let Lson::Struct(fields) = &value else { panic!() };
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["player", "flags", "log", "big", "empty"]);
+
+        assert_eq!(Lson::from_json_str("{\n  \"a\": tru }"), Err(LsonError::Json(2, 8, "expected a value".to_string())));
+        assert_eq!(Lson::from_json_str("[1, 2"), Err(LsonError::Json(1, 6, "expected a ',' or a ']'".to_string())));
+        assert_eq!(Lson::from_json_str("1 2"), Err(LsonError::Json(1, 3, "expected the end of the file".to_string())));
+        assert_eq!(Lson::from_json_str("01"), Err(LsonError::Json(1, 2, "expected the end of the file".to_string())));
+        assert_eq!(Lson::from_json_str("99999999999999999999"), Err(LsonError::IntOutOfRange("99999999999999999999".to_string())));
+        assert!(Lson::from_json_str(&"[".repeat(200)).is_err());
+    }
+
+    #[test]
+    fn json_fixture_as_runtime_data() {
+        let data = HashMap::<String, Lson>::from_lson(Lson::from_json_str(SAVE).unwrap()).unwrap();
+        let runtime = LibrettoRuntime::with_data(data);
+        assert_eq!(runtime.get_data("player")["gold"], 120);
+        assert_eq!(runtime.get_data("flags")[0], Lson::Bool(true));
+    }
+
+    #[test]
+    fn write_json() {
+        let value = crate::lson!({ name : "Bo\t\u{1}", level : 3, health : 2.0, tiny : 1e-7, pet : none, items : [[], {}] });
+        assert_eq!(
+            value.to_json_string().unwrap(),
+            r#"{"name":"Bo\t\u0001","level":3,"health":2.0,"tiny":1e-7,"pet":null,"items":[[],{}]}"#,
+        );
+        assert_eq!(
+            crate::lson!({ a : [1, 2], b : {} }).to_json_string_pretty(2).unwrap(),
+            "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {}\n}",
+        );
+
+        let value = Lson::from_json_str(SAVE).unwrap();
+        assert_eq!(Lson::from_json_str(&value.to_json_string().unwrap()).unwrap().to_json_string(), value.to_json_string());
+
+        assert_eq!(Lson::Float(f64::NAN).to_json_string(), Err(LsonError::NotFinite("NaN".to_string())));
+        let function = Lson::Function(Arc::new(|_, _| Lson::None), LsonType::None);
+        assert_eq!(crate::lson!([function]).to_json_string(), Err(LsonError::Function));
+    }
+}
@@ -0,0 +1,241 @@
+//==================================================================================================
+//          Lson Macro
+//==================================================================================================
+
+/// Builds an [`Lson`](crate::lson::Lson) value with the same syntax the logic language uses for its
+/// literals. Structs are written with `{ key : value }`, arrays with `[a, b]`, and `none`, `true`
+/// and `false` are written as themselves. Anything else is a Rust expression, turned into Lson with
+/// `Lson::from`, so values can be taken from the surrounding code:
+///
+/// ```
+/// # use libretto::lson;
+/// let gold = 12;
+/// let player = lson!({
+///     name : "Ada",
+///     gold : gold * 2,
+///     pet : none,
+///     items : ["sword", { kind : "potion", heals : 2.5 }],
+///     ("hidden-key") : true,
+/// });
+/// assert_eq!(player["gold"], 24);
+/// ```
+///
+/// Keys are names or string literals. A key in parentheses is a Rust expression.
+#[macro_export]
+macro_rules! lson {
+    //----------------------------------------------------------------------------------------------
+    // Arrays are munched one element at a time into a list of expressions.
+
+    (@array [$($elems:expr,)*]) => {
+        ::std::vec![$($elems,)*]
+    };
+
+    (@array [$($elems:expr),*]) => {
+        ::std::vec![$($elems),*]
+    };
+
+    (@array [$($elems:expr,)*] none $($rest:tt)*) => {
+        $crate::lson!(@array [$($elems,)* $crate::lson!(none)] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::lson!(@array [$($elems,)* $crate::lson!(true)] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::lson!(@array [$($elems,)* $crate::lson!(false)] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::lson!(@array [$($elems,)* $crate::lson!([$($array)*])] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] {$($object:tt)*} $($rest:tt)*) => {
+        $crate::lson!(@array [$($elems,)* $crate::lson!({$($object)*})] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::lson!(@array [$($elems,)* $crate::lson!($next),] $($rest)*)
+    };
+
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::lson!(@array [$($elems,)* $crate::lson!($last)])
+    };
+
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::lson!(@array [$($elems,)*] $($rest)*)
+    };
+
+    //----------------------------------------------------------------------------------------------
+    // Structs are munched into `(key) (rest) (copy of rest)`. The copy is only there to point error
+    // messages at the token that doesn't fit.
+
+    (@object $object:ident () () ()) => {};
+
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert($crate::lson!(@key $($key)+), $value);
+        $crate::lson!(@object $object () ($($rest)*) ($($rest)*));
+    };
+
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert($crate::lson!(@key $($key)+), $value);
+    };
+
+    (@object $object:ident ($($key:tt)+) (: none $($rest:tt)*) $copy:tt) => {
+        $crate::lson!(@object $object [$($key)+] ($crate::lson!(none)) $($rest)*);
+    };
+
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::lson!(@object $object [$($key)+] ($crate::lson!(true)) $($rest)*);
+    };
+
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::lson!(@object $object [$($key)+] ($crate::lson!(false)) $($rest)*);
+    };
+
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::lson!(@object $object [$($key)+] ($crate::lson!([$($array)*])) $($rest)*);
+    };
+
+    (@object $object:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::lson!(@object $object [$($key)+] ($crate::lson!({$($inner)*})) $($rest)*);
+    };
+
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::lson!(@object $object [$($key)+] ($crate::lson!($value)) , $($rest)*);
+    };
+
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::lson!(@object $object [$($key)+] ($crate::lson!($value)));
+    };
+
+    (@object $object:ident ($($key:tt)+) (:) $copy:tt) => {
+        $crate::lson!();
+    };
+
+    (@object $object:ident ($($key:tt)+) () $copy:tt) => {
+        $crate::lson!();
+    };
+
+    (@object $object:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        $crate::lson!(@unexpected $colon);
+    };
+
+    (@object $object:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        $crate::lson!(@unexpected $comma);
+    };
+
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::lson!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    (@key $key:ident) => {
+        ::std::string::String::from(::std::stringify!($key))
+    };
+
+    (@key $key:literal) => {
+        ::std::string::String::from($key)
+    };
+
+    (@key ($key:expr)) => {
+        ::std::string::String::from($key)
+    };
+
+    (@unexpected) => {};
+
+    //----------------------------------------------------------------------------------------------
+    // The values themselves.
+
+    (none) => {
+        $crate::lson::Lson::None
+    };
+
+    (true) => {
+        $crate::lson::Lson::Bool(true)
+    };
+
+    (false) => {
+        $crate::lson::Lson::Bool(false)
+    };
+
+    ([]) => {
+        $crate::lson::Lson::Array(::std::vec::Vec::new())
+    };
+
+    ([ $($tt:tt)+ ]) => {
+        $crate::lson::Lson::Array($crate::lson!(@array [] $($tt)+))
+    };
+
+    ({}) => {
+        $crate::lson::Lson::Struct($crate::lson::LsonMap::new())
+    };
+
+    ({ $($tt:tt)+ }) => {{
+        let mut object = $crate::lson::LsonMap::new();
+        $crate::lson!(@object object () ($($tt)+) ($($tt)+));
+        $crate::lson::Lson::Struct(object)
+    }};
+
+    ($other:expr) => {
+        $crate::lson::Lson::from($other)
+    };
+}
+
+//==================================================================================================
+//          Lson Macro Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::lson::{Lson, LsonMap};
+
+    #[test]
+    fn lson_literals() {
+        assert_eq!(lson!(none), Lson::None);
+        assert_eq!(lson!(true), Lson::Bool(true));
+        assert_eq!(lson!(12), Lson::Int(12));
+        assert_eq!(lson!(-2.5), Lson::Float(-2.5));
+        assert_eq!(lson!("String"), Lson::String("String".to_string()));
+        assert_eq!(lson!([]), Lson::Array(vec![]));
+        assert_eq!(lson!({}), Lson::Struct(LsonMap::new()));
+    }
+
+    #[test]
+    fn lson_nested() {
+        let value = lson!({
+            value : "String",
+            list : [1, none, [true, false], { inner : 2.0 }],
+            "quoted key" : { deeper : {} },
+        });
+        let expected = Lson::Struct(LsonMap::from([
+            ("value".to_string(), Lson::from("String")),
+            ("list".to_string(), Lson::Array(vec![
+                Lson::Int(1),
+                Lson::None,
+                Lson::Array(vec![Lson::Bool(true), Lson::Bool(false)]),
+                Lson::Struct(LsonMap::from([("inner".to_string(), Lson::Float(2.0))])),
+            ])),
+            ("quoted key".to_string(), Lson::Struct(LsonMap::from([("deeper".to_string(), Lson::Struct(LsonMap::new()))]))),
+        ]));
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn lson_interpolated() {
+        let name = "Ada";
+        let items = vec!["sword", "shield"];
+        let key = format!("level_{}", 2);
+        let value = lson!({
+            name : name,
+            gold : 10 * 3,
+            items : items.clone(),
+            (key.as_str()) : name.len() as i64,
+            last : lson!([name, "Bo"])
+        });
+        assert_eq!(value["name"], "Ada");
+        assert_eq!(value["gold"], 30);
+        assert_eq!(value["items"], Lson::from(items));
+        assert_eq!(value["level_2"], 3);
+        assert_eq!(value["last"][1], "Bo");
+    }
+}
@@ -0,0 +1,361 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Lson, LsonError, LsonIndex};
+
+//==================================================================================================
+//          Lson Path
+//==================================================================================================
+
+/// One step into a value: a field of a struct, or a position in an array.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LsonPathSegment {
+    Field(String),
+    Index(usize),
+}
+
+impl LsonPathSegment {
+    /// Whether the field can be written as `.name`, or needs the quoted `["name"]` form.
+    fn is_plain(name: &str) -> bool {
+        !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+    }
+}
+
+impl fmt::Display for LsonPathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LsonPathSegment::Field(name) if Self::is_plain(name) => write!(f, ".{}", name),
+            LsonPathSegment::Field(name) => write!(f, "[\"{}\"]", name.replace('\\', "\\\\").replace('"', "\\\"")),
+            LsonPathSegment::Index(index) => write!(f, "[{}]", index),
+        }
+    }
+}
+
+impl LsonIndex for LsonPathSegment {
+    fn index_into<'l>(&self, value: &'l Lson) -> Option<&'l Lson> {
+        match self {
+            LsonPathSegment::Field(name) => name.index_into(value),
+            LsonPathSegment::Index(index) => index.index_into(value),
+        }
+    }
+
+    fn index_into_mut<'l>(&self, value: &'l mut Lson) -> Option<&'l mut Lson> {
+        match self {
+            LsonPathSegment::Field(name) => name.index_into_mut(value),
+            LsonPathSegment::Index(index) => index.index_into_mut(value),
+        }
+    }
+
+    fn index_or_insert<'l>(&self, value: &'l mut Lson) -> &'l mut Lson {
+        match self {
+            LsonPathSegment::Field(name) => name.index_or_insert(value),
+            LsonPathSegment::Index(index) => index.index_or_insert(value),
+        }
+    }
+}
+
+/// The address of a value nested inside of another, written the way it would be in a script:
+/// `roster.values[3].name`. Fields that aren't plain names are quoted, as in `stats["max hp"]`.
+/// The empty path is the value itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct LsonPath {
+    segments: Vec<LsonPathSegment>,
+}
+
+impl LsonPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn segments(&self) -> &[LsonPathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn push(&mut self, segment: LsonPathSegment) {
+        self.segments.push(segment);
+    }
+
+    pub fn pop(&mut self) -> Option<LsonPathSegment> {
+        self.segments.pop()
+    }
+
+    /// The path with `segment` added to the end of it.
+    pub fn join(&self, segment: LsonPathSegment) -> Self {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+
+    /// The path up to and including the segment at `position`, written out for an error.
+    fn written_to(&self, position: usize) -> String {
+        LsonPath { segments: self.segments[..=position].to_vec() }.to_string()
+    }
+
+    /// Explains why the segment at `position` couldn't be found in `value`.
+    pub(crate) fn failure(&self, position: usize, value: &Lson) -> LsonPathError {
+        let path = self.written_to(position);
+        match (&self.segments[position], value) {
+            (LsonPathSegment::Field(name), Lson::Struct(_)) => LsonPathError::MissingField(path, name.clone()),
+            (LsonPathSegment::Field(_), value) => LsonPathError::NotAStruct(path, value.get_type().to_string()),
+            (LsonPathSegment::Index(index), Lson::Array(values)) => LsonPathError::IndexOutOfBounds(path, *index, values.len()),
+            (LsonPathSegment::Index(_), value) => LsonPathError::NotAnArray(path, value.get_type().to_string()),
+        }
+    }
+}
+
+impl From<Vec<LsonPathSegment>> for LsonPath {
+    fn from(segments: Vec<LsonPathSegment>) -> Self {
+        LsonPath { segments }
+    }
+}
+
+impl fmt::Display for LsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (position, segment) in self.segments.iter().enumerate() {
+            match segment {
+                LsonPathSegment::Field(name) if position == 0 && LsonPathSegment::is_plain(name) => f.write_str(name)?,
+                segment => write!(f, "{}", segment)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for LsonPath {
+    type Err = LsonPathError;
+
+    fn from_str(text: &str) -> Result<Self, LsonPathError> {
+        let fail = |message: &str| LsonPathError::Syntax(text.to_string(), message.to_string());
+        let mut segments = Vec::new();
+        let mut chars = text.chars().peekable();
+
+        while let Some(&next) = chars.peek() {
+            match next {
+                '[' => {
+                    chars.next();
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        let mut name = String::new();
+                        loop {
+                            match chars.next() {
+                                Some('"') => break,
+                                Some('\\') => name.push(chars.next().ok_or_else(|| fail("the quoted field is never closed"))?),
+                                Some(c) => name.push(c),
+                                None => return Err(fail("the quoted field is never closed")),
+                            }
+                        }
+                        segments.push(LsonPathSegment::Field(name));
+                    } else {
+                        let mut digits = String::new();
+                        while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                            digits.push(c);
+                        }
+                        let index = digits.parse().map_err(|_| fail("an index must be a positive int"))?;
+                        segments.push(LsonPathSegment::Index(index));
+                    }
+                    if chars.next() != Some(']') {
+                        return Err(fail("expected a ']'"));
+                    }
+                },
+                _ => {
+                    if next == '.' {
+                        if segments.is_empty() {
+                            return Err(fail("a path can't start with a '.'"));
+                        }
+                        chars.next();
+                    } else if !segments.is_empty() {
+                        return Err(fail("fields must be separated by a '.'"));
+                    }
+                    let mut name = String::new();
+                    while let Some(c) = chars.next_if(|c| !matches!(c, '.' | '[' | ']' | '"' | '\\')) {
+                        name.push(c);
+                    }
+                    if name.is_empty() {
+                        return Err(fail("expected the name of a field"));
+                    }
+                    segments.push(LsonPathSegment::Field(name));
+                },
+            }
+        }
+        Ok(LsonPath { segments })
+    }
+}
+
+/// Paths are written in their text form, so patches and other values holding them stay readable.
+impl Serialize for LsonPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LsonPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+//==================================================================================================
+//          Lookups
+//==================================================================================================
+
+impl Lson {
+    /// Looks up a value nested inside of this one, like `"roster.values[3].name"`. Unlike indexing,
+    /// which gives `none` for anything missing, this says which segment of the path went wrong.
+    pub fn pointer(&self, path: &str) -> Result<&Lson, LsonPathError> {
+        self.lookup(&path.parse()?)
+    }
+
+    pub fn pointer_mut(&mut self, path: &str) -> Result<&mut Lson, LsonPathError> {
+        self.lookup_mut(&path.parse()?)
+    }
+
+    pub fn lookup(&self, path: &LsonPath) -> Result<&Lson, LsonPathError> {
+        let mut current = self;
+        for (position, segment) in path.segments.iter().enumerate() {
+            current = segment.index_into(current).ok_or_else(|| path.failure(position, current))?;
+        }
+        Ok(current)
+    }
+
+    pub fn lookup_mut(&mut self, path: &LsonPath) -> Result<&mut Lson, LsonPathError> {
+        let mut current = self;
+        for (position, segment) in path.segments.iter().enumerate() {
+            if segment.index_into(current).is_none() {
+                return Err(path.failure(position, current));
+            }
+            current = segment.index_into_mut(current).unwrap();
+        }
+        Ok(current)
+    }
+
+    /// Looks up a nested value and converts it, so `value.extract::<Vec<String>>("party.names")`
+    /// either gives the names or says what was missing or of the wrong type.
+    pub fn extract<T: TryFrom<Lson, Error = LsonError>>(&self, path: &str) -> Result<T, LsonError> {
+        let path: LsonPath = path.parse()?;
+        T::try_from(self.lookup(&path)?.clone()).map_err(|error| match path.is_empty() {
+            true => error,
+            false => LsonError::InField(path.to_string(), Box::new(error)),
+        })
+    }
+}
+
+//==================================================================================================
+//          Lson Path Error
+//==================================================================================================
+
+/// An error from following a path into a value. Each names the path up to the segment that failed.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LsonPathError {
+    #[error("The path '{0}' is not valid, {1}.")]
+    Syntax(String, String),
+
+    #[error("At '{0}': the struct has no field named '{1}'.")]
+    MissingField(String, String),
+
+    #[error("At '{0}': index {1} is past the end of an array of length {2}.")]
+    IndexOutOfBounds(String, usize, usize),
+
+    #[error("At '{0}': a value of type {1} has no fields.")]
+    NotAStruct(String, String),
+
+    #[error("At '{0}': a value of type {1} can't be indexed.")]
+    NotAnArray(String, String),
+
+    #[error("The empty path is the whole value, which can be set but not inserted or removed.")]
+    WholeValue,
+}
+
+//==================================================================================================
+//          Lson Path Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::lson::{Lson, LsonError, LsonPath, LsonPathError, LsonPathSegment};
+
+    fn roster() -> Lson {
+        crate::lson!({
+            roster : {
+                values : [
+                    { name : "Ada", level : 3 },
+                    { name : "Bo", level : 1 },
+                ],
+            },
+            stats : { "max hp" : 12 },
+        })
+    }
+
+    #[test]
+    fn parse_path() {
+        let path: LsonPath = "roster.values[1].name".parse().unwrap();
+        assert_eq!(path.segments(), &[
+            LsonPathSegment::Field("roster".to_string()),
+            LsonPathSegment::Field("values".to_string()),
+            LsonPathSegment::Index(1),
+            LsonPathSegment::Field("name".to_string()),
+        ]);
+        assert_eq!(path.to_string(), "roster.values[1].name");
+
+        let path: LsonPath = "[2][\"max hp\"].a".parse().unwrap();
+        assert_eq!(path.to_string(), "[2][\"max hp\"].a");
+        assert!("".parse::<LsonPath>().unwrap().is_empty());
+
+        for invalid in [".a", "a..b", "a[x]", "a[1", "a[\"b]", "a[1]b"] {
+            assert!(matches!(invalid.parse::<LsonPath>(), Err(LsonPathError::Syntax(..))), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn pointer_lookup() {
+        let mut value = roster();
+        assert_eq!(*value.pointer("roster.values[1].name").unwrap(), "Bo");
+        assert_eq!(*value.pointer("stats[\"max hp\"]").unwrap(), 12);
+        assert_eq!(*value.pointer("").unwrap(), roster());
+
+        *value.pointer_mut("roster.values[0].level").unwrap() = Lson::Int(4);
+        assert_eq!(value["roster"]["values"][0]["level"], 4);
+
+        assert_eq!(value.pointer("roster.values[3].name"), Err(LsonPathError::IndexOutOfBounds("roster.values[3]".to_string(), 3, 2)));
+        assert_eq!(value.pointer("roster.players").unwrap_err().to_string(), "At 'roster.players': the struct has no field named 'players'.");
+        assert_eq!(value.pointer("roster.values[0].name.first"), Err(LsonPathError::NotAStruct("roster.values[0].name.first".to_string(), "string".to_string())));
+        assert_eq!(value.pointer("stats[0]"), Err(LsonPathError::NotAnArray("stats[0]".to_string(), "struct".to_string())));
+    }
+
+    #[test]
+    fn try_from_lson() {
+        assert_eq!(i64::try_from(Lson::Int(3)), Ok(3));
+        assert_eq!(f64::try_from(Lson::Int(3)), Ok(3.0));
+        assert_eq!(Option::<bool>::try_from(Lson::None), Ok(None));
+        assert_eq!(Vec::<String>::try_from(Lson::from(["a", "b"])), Ok(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(
+            HashMap::<String, i32>::try_from(crate::lson!({ a : 1 })),
+            Ok(HashMap::from([("a".to_string(), 1)])),
+        );
+        assert_eq!(
+            Vec::<i64>::try_from(crate::lson!([1, "two"])),
+            Err(LsonError::InField("1".to_string(), Box::new(LsonError::WrongType("int".to_string(), "string".to_string())))),
+        );
+    }
+
+    #[test]
+    fn extract_values() {
+        let value = roster();
+        assert_eq!(value.extract::<u8>("roster.values[0].level"), Ok(3));
+        assert_eq!(value.extract::<String>("roster.values[1].name"), Ok("Bo".to_string()));
+        assert_eq!(
+            value.extract::<bool>("roster.values[1].name"),
+            Err(LsonError::InField(
+                "roster.values[1].name".to_string(),
+                Box::new(LsonError::WrongType("bool".to_string(), "string".to_string())),
+            )),
+        );
+        assert!(matches!(value.extract::<i64>("roster.values[5]"), Err(LsonError::Path(LsonPathError::IndexOutOfBounds(..)))));
+    }
+}
@@ -0,0 +1,354 @@
+use serde::{ser, Serialize};
+
+use super::{Lson, LsonError, LsonMap};
+
+//==================================================================================================
+//          Serialize
+//==================================================================================================
+
+/// Lson serializes the way the values would be written by hand: ints, floats, strings and bools as
+/// themselves, arrays as sequences, structs as maps and `None` as a missing value.
+impl Serialize for Lson {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Lson::None => serializer.serialize_none(),
+            Lson::Int(value) => serializer.serialize_i64(*value),
+            Lson::Float(value) => serializer.serialize_f64(*value),
+            Lson::String(value) => serializer.serialize_str(value),
+            Lson::Bool(value) => serializer.serialize_bool(*value),
+            Lson::Array(values) => serializer.collect_seq(values),
+            Lson::Struct(fields) => serializer.collect_map(fields),
+            Lson::Function(..) => Err(ser::Error::custom(LsonError::Function)),
+        }
+    }
+}
+
+/// Turns any serializable value into Lson, so it can be handed to a script. Structs and maps become
+/// `Lson::Struct`, sequences and tuples become `Lson::Array`, and unit values become `Lson::None`.
+/// Enum variants are written the way most formats write them: a unit variant is its name, and any
+/// other variant is a struct with the name as its only field.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Lson, LsonError> {
+    value.serialize(LsonSerializer)
+}
+
+//==================================================================================================
+//          Lson Serializer
+//==================================================================================================
+
+struct LsonSerializer;
+
+impl ser::Serializer for LsonSerializer {
+    type Ok = Lson;
+    type Error = LsonError;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+    type SerializeMap = SerializeStruct;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = SerializeVariant<SerializeStruct>;
+
+    fn serialize_bool(self, value: bool) -> Result<Lson, LsonError> {
+        Ok(Lson::Bool(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Lson, LsonError> {
+        Ok(Lson::Int(value.into()))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Lson, LsonError> {
+        Ok(Lson::Int(value.into()))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Lson, LsonError> {
+        Ok(Lson::Int(value.into()))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Lson, LsonError> {
+        Ok(Lson::Int(value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Lson, LsonError> {
+        Ok(Lson::Int(value.into()))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Lson, LsonError> {
+        Ok(Lson::Int(value.into()))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Lson, LsonError> {
+        Ok(Lson::Int(value.into()))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Lson, LsonError> {
+        i64::try_from(value).map(Lson::Int).map_err(|_| LsonError::IntOutOfRange(value.to_string()))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<Lson, LsonError> {
+        i64::try_from(value).map(Lson::Int).map_err(|_| LsonError::IntOutOfRange(value.to_string()))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<Lson, LsonError> {
+        i64::try_from(value).map(Lson::Int).map_err(|_| LsonError::IntOutOfRange(value.to_string()))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Lson, LsonError> {
+        Ok(Lson::Float(value.into()))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Lson, LsonError> {
+        Ok(Lson::Float(value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Lson, LsonError> {
+        Ok(Lson::String(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Lson, LsonError> {
+        Ok(Lson::String(value.to_string()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Lson, LsonError> {
+        Ok(Lson::Array(value.iter().map(|byte| Lson::Int((*byte).into())).collect()))
+    }
+
+    fn serialize_none(self) -> Result<Lson, LsonError> {
+        Ok(Lson::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Lson, LsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Lson, LsonError> {
+        Ok(Lson::None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Lson, LsonError> {
+        Ok(Lson::None)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Lson, LsonError> {
+        Ok(Lson::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Lson, LsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _index: u32, variant: &'static str, value: &T) -> Result<Lson, LsonError> {
+        Ok(Lson::Struct(LsonMap::from([(variant.to_string(), value.serialize(self)?)])))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, LsonError> {
+        Ok(SerializeArray { values: Vec::with_capacity(len.unwrap_or(0)) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, LsonError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeArray, LsonError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, LsonError> {
+        Ok(SerializeVariant { variant, inner: self.serialize_seq(Some(len))? })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeStruct, LsonError> {
+        Ok(SerializeStruct { fields: LsonMap::with_capacity(len.unwrap_or(0)), key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeStruct, LsonError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant, LsonError> {
+        Ok(SerializeVariant { variant, inner: self.serialize_map(Some(len))? })
+    }
+}
+
+struct SerializeArray {
+    values: Vec<Lson>,
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Lson;
+    type Error = LsonError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LsonError> {
+        self.values.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Lson, LsonError> {
+        Ok(Lson::Array(self.values))
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Lson;
+    type Error = LsonError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LsonError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Lson, LsonError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Lson;
+    type Error = LsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LsonError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Lson, LsonError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+struct SerializeStruct {
+    fields: LsonMap,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeStruct {
+    type Ok = Lson;
+    type Error = LsonError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), LsonError> {
+        self.key = Some(match to_value(key)? {
+            Lson::String(key) => key,
+            Lson::Int(key) => key.to_string(),
+            Lson::Bool(key) => key.to_string(),
+            key => return Err(LsonError::KeyNotString(key.get_type().to_string())),
+        });
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LsonError> {
+        let key = self.key.take().expect("serialize_value is called after serialize_key");
+        self.fields.insert(key, to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Lson, LsonError> {
+        Ok(Lson::Struct(self.fields))
+    }
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+    type Ok = Lson;
+    type Error = LsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), LsonError> {
+        self.fields.insert(key.to_string(), to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Lson, LsonError> {
+        Ok(Lson::Struct(self.fields))
+    }
+}
+
+/// Wraps the contents of a tuple or struct variant in a struct named after the variant.
+struct SerializeVariant<T> {
+    variant: &'static str,
+    inner: T,
+}
+
+impl<T> SerializeVariant<T> {
+    fn wrap(variant: &'static str, value: Lson) -> Lson {
+        Lson::Struct(LsonMap::from([(variant.to_string(), value)]))
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+    type Ok = Lson;
+    type Error = LsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), LsonError> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Lson, LsonError> {
+        Ok(Self::wrap(self.variant, ser::SerializeSeq::end(self.inner)?))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeStruct> {
+    type Ok = Lson;
+    type Error = LsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), LsonError> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Lson, LsonError> {
+        Ok(Self::wrap(self.variant, ser::SerializeStruct::end(self.inner)?))
+    }
+}
+
+//==================================================================================================
+//          Serialize Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use serde::Serialize;
+
+    use crate::lson::{to_value, Lson, LsonError, LsonType};
+
+    #[derive(Serialize)]
+    enum Class {
+        Knight,
+        Mage { school: String },
+        Rogue(u32),
+    }
+
+    #[derive(Serialize)]
+    struct Hero {
+        name: String,
+        level: u8,
+        pet: Option<String>,
+        classes: Vec<Class>,
+    }
+
+    #[test]
+    fn serialize_to_value() {
+        let hero = Hero {
+            name: "Ada".to_string(),
+            level: 3,
+            pet: None,
+            classes: vec![Class::Knight, Class::Mage { school: "fire".to_string() }, Class::Rogue(2)],
+        };
+        let expected = Lson::from(HashMap::from([
+            ("name", Lson::from("Ada")),
+            ("level", Lson::Int(3)),
+            ("pet", Lson::None),
+            ("classes", Lson::Array(vec![
+                Lson::from("Knight"),
+                Lson::from(HashMap::from([("Mage", HashMap::from([("school", "fire")]))])),
+                Lson::from(HashMap::from([("Rogue", Lson::Int(2))])),
+            ])),
+        ]));
+        assert_eq!(to_value(&hero).unwrap(), expected);
+        assert_eq!(to_value(&(1.5, 'x', ())).unwrap(), Lson::Array(vec![Lson::Float(1.5), Lson::from("x"), Lson::None]));
+        assert_eq!(to_value(&HashMap::from([(4, true)])).unwrap(), Lson::from(HashMap::from([("4", true)])));
+
+        assert_eq!(to_value(&u64::MAX), Err(LsonError::IntOutOfRange(u64::MAX.to_string())));
+        assert_eq!(to_value(&HashMap::from([((), 1)])), Err(LsonError::KeyNotString("none".to_string())));
+
+        let function = Lson::Function(Arc::new(|_, _| Lson::None), LsonType::None);
+        assert_eq!(to_value(&function), Err(LsonError::Custom(LsonError::Function.to_string())));
+    }
+}
@@ -7,8 +7,22 @@ mod logic_term_expr;
 mod logic_factor_expr;
 mod logic_comparison_expr;
 mod logic_equality_expr;
+mod logic_yield_stmt;
+mod logic_request_stmt;
+mod logic_stmt;
+mod logic_block;
+mod logic_condition;
+mod logic_guard;
+mod dialog_line;
+mod dialog_response;
+mod dialog_target;
+mod dialog;
+mod script;
 mod util;
 
+pub use dialog_target::DialogTarget;
+pub use script::Script;
+
 use logos::Logos;
 use std::{
     fmt::Debug, collections::HashMap,
@@ -17,7 +31,7 @@ use thiserror::Error;
 
 use crate::{
     lson::{LsonType, Lson},
-    lexer::{LibrettoTokenQueue, Ordinal}, runtime::LibrettoRuntime, compiler::LibrettoCompiletime,
+    lexer::{LibrettoTokenQueue, Ordinal}, runtime::LibrettoRuntime, compiler::{LibrettoCompiletime, LibrettoCompileErrorKind},
 };
 
 //==================================================================================================
@@ -26,7 +40,7 @@ use crate::{
 
 pub trait LibrettoParsable<'a, T>
 where
-    T: Logos<'a> + PartialEq + Ordinal + Clone + Debug + 'a,
+    T: Logos<'a, Source = str> + PartialEq + Ordinal + Clone + Debug + 'a,
     T::Extras: Clone,
     Self: Sized,
 {
@@ -55,6 +69,44 @@ where
             None
         }
     }
+
+    /// Parses the whole queue as one node, like the contents of a logic block. Unlike
+    /// `checked_parse`, a queue that doesn't hold exactly one node pushes a syntax error.
+    fn parse_whole(queue: &mut LibrettoTokenQueue<'a, T>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        queue.reset();
+        queue.clear_failure();
+        if Self::raw_check(queue) {
+            if queue.is_exhausted() {
+                queue.reset();
+                return Self::parse(queue, compile_time);
+            }
+            queue.fail_at(queue.cursor(), &["the end of the block"]);
+        }
+        push_syntax_error(queue, compile_time);
+        None
+    }
+}
+
+/// Pushes the furthest failure recorded in the queue as a syntax error. When nothing was recorded,
+/// the next token is reported instead.
+pub(crate) fn push_syntax_error<'a, T>(queue: &mut LibrettoTokenQueue<'a, T>, compile_time : &mut LibrettoCompiletime)
+where
+    T: Logos<'a, Source = str> + PartialEq + Ordinal + Clone + Debug + 'a,
+    T::Extras: Clone,
+{
+    let failure = match queue.take_failure() {
+        Some(failure) => failure,
+        None => {
+            queue.fail_at(queue.cursor(), &[]);
+            queue.take_failure().unwrap()
+        }
+    };
+    let expected = match failure.expected.as_slice() {
+        [] => "something else".to_string(),
+        [only] => only.to_string(),
+        [rest @ .., last] => format!("{} or {}", rest.join(", "), last),
+    };
+    compile_time.push_error(LibrettoCompileErrorKind::UnexpectedToken(expected, failure.found), failure.span);
 }
 
 #[macro_export]
@@ -70,7 +122,8 @@ macro_rules! parse_ast {
     };
 }
 
-pub mod test_util {
+#[cfg(test)]
+pub(crate) mod test_util {
 
     use std::collections::HashMap;
 
@@ -78,7 +131,7 @@ pub mod test_util {
 
     use crate::compiler::LibrettoCompiletime;
     use crate::lson::{LsonType, Lson};
-    use crate::lexer::{LibrettoLogicToken, LibrettoTokenQueue};
+    use crate::lexer::{LibrettoLogicToken, LibrettoToken, LibrettoTokenQueue};
     use crate::runtime::{LibrettoRuntime, LibrettoEvaluator};
 
     use super::{LibrettoParsable};
@@ -148,4 +201,44 @@ pub mod test_util {
         assert_eq!(result, lson);
         runtime
     }
-}
\ No newline at end of file
+
+    pub fn check_dialog<'a, T: LibrettoParsable<'a, LibrettoToken<'a>>>(
+        source: &'a str,
+        number_of_tokens: usize,
+    ) {
+        let mut queue = LibrettoTokenQueue::from(LibrettoToken::lexer(source));
+        let check = T::check(&mut queue);
+        assert!(check);
+        assert_eq!(queue.cursor(), 0);
+        queue.reset();
+        let check = T::raw_check(&mut queue);
+        assert!(check);
+        assert_eq!(queue.cursor(), number_of_tokens)
+    }
+
+    pub fn parse_dialog<'a, T: LibrettoParsable<'a, LibrettoToken<'a>>>(source: &'a str) -> T {
+        let mut queue = LibrettoTokenQueue::from(LibrettoToken::lexer(source));
+        let mut compile_time = LibrettoCompiletime::with_data([
+            (String::from("foo"), LsonType::Float),
+            (String::from("bar"), LsonType::Bool),
+        ]);
+        let result = T::checked_parse(&mut queue, &mut compile_time);
+        assert!(result.is_some());
+        result.unwrap()
+    }
+
+    pub fn validate_dialog<'a, T: LibrettoParsable<'a, LibrettoToken<'a>>>(
+        source: &'a str,
+        number_of_errors: usize,
+    ) {
+        let mut queue = LibrettoTokenQueue::from(LibrettoToken::lexer(source));
+        let mut compile_time = LibrettoCompiletime::with_data([
+            (String::from("foo"), LsonType::Float),
+            (String::from("bar"), LsonType::Bool),
+        ]);
+        let ast = T::checked_parse(&mut queue, &mut compile_time);
+        assert!(ast.is_some());
+        ast.unwrap().validate(&mut compile_time);
+        assert_eq!(compile_time.error_count(), number_of_errors);
+    }
+}
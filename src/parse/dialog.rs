@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::LibrettoCompiletime,
+    lexer::{LibrettoToken, LibrettoTokenQueue, TokenOrdinal},
+    lson::LsonType,
+    span::Span,
+};
+
+use super::{dialog_line::DialogLine, dialog_response::ResponseTarget, dialog_target::DialogTarget, push_syntax_error, LibrettoParsable};
+
+//==================================================================================================
+//          Dialog
+//==================================================================================================
+
+/// A named sequence of lines, written as `name { | ... | ... }`.
+#[derive(Serialize, Deserialize)]
+pub struct Dialog {
+    name: String,
+    lines: Vec<DialogLine>,
+    span: Span,
+}
+
+impl Dialog {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether this dialog was written inline after a response, and given a generated name.
+    pub fn is_inline(&self) -> bool {
+        self.name.contains('#')
+    }
+
+    pub fn lines(&self) -> &Vec<DialogLine> {
+        &self.lines
+    }
+
+    /// The index of every line in this dialog with the given label.
+    pub fn label_lines<'s>(&'s self, label: &'s str) -> impl Iterator<Item = usize> + 's {
+        self.lines.iter().enumerate().filter(move |(_, line)| line.label() == Some(label)).map(|(index, _)| index)
+    }
+
+    /// Every target written in this dialog.
+    pub fn targets(&self) -> impl Iterator<Item = &DialogTarget> {
+        self.lines.iter().flat_map(|line| line.targets())
+    }
+
+    pub(super) fn targets_mut(&mut self) -> impl Iterator<Item = &mut DialogTarget> {
+        self.lines.iter_mut().flat_map(|line| line.targets_mut())
+    }
+
+    /// Moves every inline response dialog out of this dialog and gives it a generated name, so the
+    /// runtime can jump to it like any other dialog. Nested inline dialogs are moved out as well.
+    pub fn take_inline_dialogs(&mut self) -> Vec<Dialog> {
+        let mut inline = Vec::new();
+        let mut count = 0;
+        for line in self.lines.iter_mut() {
+            for response in line.responses_mut() {
+                if let ResponseTarget::Inline(_) = response.target() {
+                    let name = format!("{}#{}", self.name, count);
+                    count += 1;
+                    let span = response.target().span();
+                    let target = std::mem::replace(response.target_mut(), ResponseTarget::Dialog(DialogTarget::new(&name, None).at(span)));
+                    if let ResponseTarget::Inline(mut dialog) = target {
+                        dialog.name = name;
+                        let nested = dialog.take_inline_dialogs();
+                        inline.push(dialog);
+                        inline.extend(nested);
+                    }
+                }
+            }
+        }
+        inline
+    }
+
+    pub(super) fn raw_check_body<'a>(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>) -> bool {
+        if !queue.next_is(TokenOrdinal::LeftCurlyBracket) {
+            return false;
+        }
+        while DialogLine::raw_check(queue) {}
+        queue.next_is(TokenOrdinal::RightCurlyBracket)
+    }
+
+    /// Parses the `{ ... }` of a dialog. A line that doesn't parse is reported and skipped up to the
+    /// next `|` or `}`, so every broken line in a dialog is reported at once.
+    pub(super) fn parse_body<'a>(name: String, queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        if queue.pop_if_next_is(TokenOrdinal::LeftCurlyBracket).is_none() {
+            push_syntax_error(queue, compile_time);
+            return None;
+        }
+
+        let mut lines = Vec::new();
+        while queue.pop_if_next_is(TokenOrdinal::RightCurlyBracket).is_none() {
+            if !queue.next_is(TokenOrdinal::Bar) {
+                push_syntax_error(queue, compile_time);
+                if queue.is_exhausted() {
+                    break;
+                }
+                queue.skip_until([TokenOrdinal::Bar, TokenOrdinal::RightCurlyBracket]);
+                continue;
+            }
+
+            queue.reset();
+            queue.clear_failure();
+            match DialogLine::parse(queue, compile_time) {
+                Some(line) => lines.push(line),
+                None => queue.skip_until([TokenOrdinal::Bar, TokenOrdinal::RightCurlyBracket]),
+            }
+        }
+
+        Some(Dialog { name, lines, span: start.to(queue.last_span()) })
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoToken<'a>> for Dialog {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>) -> bool {
+        queue.next_is(TokenOrdinal::Identifier) && Self::raw_check_body(queue)
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        let name = if let Some(LibrettoToken::Identifier(name)) = queue.pop_if_next_is(TokenOrdinal::Identifier) {
+            name
+        } else {
+            push_syntax_error(queue, compile_time);
+            return None;
+        };
+        let mut dialog = Self::parse_body(name, queue, compile_time)?;
+        dialog.span = start.to(dialog.span);
+        Some(dialog)
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        for line in self.lines.iter() {
+            line.validate(compile_time);
+        }
+        LsonType::None
+    }
+}
+
+//==================================================================================================
+//          Dialog Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::test_util::*;
+
+    use super::*;
+
+    #[test]
+    fn check_dialog_block() {
+        check_dialog::<Dialog>("start {}", 3);
+        check_dialog::<Dialog>("start { | :Jake \"Hello.\" | \"Bye.\" }", 8);
+    }
+
+    #[test]
+    fn parse_dialog_block() {
+        let ast = parse_dialog::<Dialog>("start { | :Jake \"Hello.\" | \"Bye.\" }");
+        assert_eq!(ast.name(), "start");
+        assert_eq!(ast.lines().len(), 2);
+        assert_eq!(ast.span(), Span::new(0, 35, 1, 1));
+        assert_eq!(ast.lines()[1].span(), Span::new(25, 33, 1, 26));
+    }
+
+    #[test]
+    fn validate_dialog_block() {
+        validate_dialog::<Dialog>("start { | <let test = 2;> | <test = 3> }", 1);
+        validate_dialog::<Dialog>("start { | <let test = 2;> <test = 3> }", 0);
+    }
+
+    #[test]
+    fn take_inline_dialogs() {
+        let mut ast = parse_dialog::<Dialog>("start { | \"Hello.\" -> \"Hi.\" { | \"Bye.\" -> \"Wait!\" { | \"What?\" } } -> \"Leave.\" end }");
+        let inline = ast.take_inline_dialogs();
+        let names: Vec<&str> = inline.iter().map(|dialog| dialog.name()).collect();
+        assert_eq!(names, vec!["start#0", "start#0#0"]);
+
+        let targets: Vec<String> = ast.targets().map(|target| target.to_string()).collect();
+        assert_eq!(targets, vec!["start#0", "end"]);
+    }
+}
@@ -0,0 +1,638 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::{LibrettoCompileErrorKind, LibrettoCompiletime},
+    lexer::{LibrettoToken, LibrettoTokenQueue, LogicOrdinal, TokenOrdinal},
+    lson::{Lson, LsonType},
+    parse_ast,
+    runtime::{dialog::{LibrettoLine, LibrettoResponse}, LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeError, LibrettoRuntimeErrorKind},
+    span::Span,
+    speaker::LibrettoSpeaker,
+};
+
+use super::{
+    dialog_response::{DialogResponse, ResponseTarget},
+    dialog_target::DialogTarget,
+    logic_block::LogicBlock,
+    logic_condition::LogicCondition,
+    logic_expr::LogicExpr,
+    push_syntax_error, LibrettoParsable,
+};
+
+//==================================================================================================
+//          Line Content
+//==================================================================================================
+
+/// Everything that can be written into a line: logic blocks, the quote, tags, responses and a divert.
+#[derive(Default, Serialize, Deserialize)]
+pub struct LineContent {
+    blocks: Vec<LogicBlock>,
+    text: Option<String>,
+    tags: Vec<String>,
+    responses: Vec<DialogResponse>,
+    divert: Option<DialogTarget>,
+}
+
+impl LineContent {
+    pub fn blocks(&self) -> &Vec<LogicBlock> {
+        &self.blocks
+    }
+
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn responses(&self) -> &Vec<DialogResponse> {
+        &self.responses
+    }
+
+    /// Where the dialog goes after this line, written as `-- target`.
+    pub fn divert(&self) -> Option<&DialogTarget> {
+        self.divert.as_ref()
+    }
+
+    fn targets(&self) -> impl Iterator<Item = &DialogTarget> {
+        self.responses.iter().filter_map(|response| match response.target() {
+            ResponseTarget::Dialog(target) => Some(target),
+            ResponseTarget::Inline(_) => None,
+        }).chain(self.divert.iter())
+    }
+
+    fn targets_mut(&mut self) -> impl Iterator<Item = &mut DialogTarget> {
+        self.responses.iter_mut().filter_map(|response| match response.target_mut() {
+            ResponseTarget::Dialog(target) => Some(target),
+            ResponseTarget::Inline(_) => None,
+        }).chain(self.divert.iter_mut())
+    }
+
+    fn run(&self, runtime: &mut LibrettoRuntime) -> Result<(), LibrettoRuntimeError> {
+        for block in self.blocks.iter() {
+            block.evaluate(runtime)?;
+        }
+        Ok(())
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) {
+        for block in self.blocks.iter() {
+            block.validate(compile_time);
+        }
+        for response in self.responses.iter() {
+            response.validate(compile_time);
+        }
+    }
+}
+
+//==================================================================================================
+//          Line Speaker
+//==================================================================================================
+
+/// Who is speaking a line. Either a name written as `:Jake`, or an expression written as `:<expr>`
+/// that is evaluated when the line is reached.
+#[derive(Serialize, Deserialize)]
+pub enum LineSpeaker {
+    Name(String, Span),
+    Expr(Box<LogicExpr>),
+}
+
+impl LineSpeaker {
+    pub fn span(&self) -> Span {
+        match self {
+            LineSpeaker::Name(_, span) => *span,
+            LineSpeaker::Expr(expr) => expr.span(),
+        }
+    }
+
+    fn resolve(&self, runtime: &mut LibrettoRuntime) -> Result<LibrettoSpeaker, LibrettoRuntimeError> {
+        let speaker = match self {
+            LineSpeaker::Name(name, _) => runtime.resolve_speaker(name),
+            LineSpeaker::Expr(expr) => {
+                let value = expr.evaluate(runtime)?;
+                match value.as_str() {
+                    Some(name) => runtime.resolve_speaker(name),
+                    None => Err(LibrettoRuntimeErrorKind::SpeakerNotString(value.get_type().to_string()).into()),
+                }
+            }
+        };
+        speaker.map_err(|error| error.or_at(self.span()))
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) {
+        match self {
+            LineSpeaker::Name(name, span) => {
+                if !compile_time.is_speaker_known(name) {
+                    compile_time.push_error(LibrettoCompileErrorKind::SpeakerNotDefined(name.clone()), *span);
+                }
+            }
+            LineSpeaker::Expr(expr) => {
+                let expr_type = expr.validate(compile_time);
+                if expr_type != LsonType::String {
+                    compile_time.push_error(LibrettoCompileErrorKind::SpeakerNotString(expr_type.to_string()), expr.span());
+                }
+            }
+        }
+    }
+}
+
+//==================================================================================================
+//          Line Variant
+//==================================================================================================
+
+/// An alternate version of a line, chosen with `<if cond>`, `<else if cond>` or `<else>`.
+#[derive(Serialize, Deserialize)]
+pub struct LineVariant {
+    condition: LogicCondition,
+    content: LineContent,
+}
+
+impl LineVariant {
+    pub fn condition(&self) -> &LogicCondition {
+        &self.condition
+    }
+
+    pub fn content(&self) -> &LineContent {
+        &self.content
+    }
+}
+
+//==================================================================================================
+//          Dialog Line
+//==================================================================================================
+
+/// A presented line, along with where each of its responses and its divert lead to. A response that
+/// is shown but can't be picked has no target.
+pub struct PresentedLine {
+    pub line: LibrettoLine,
+    pub choices: Vec<Option<DialogTarget>>,
+    pub divert: Option<DialogTarget>,
+}
+
+/// A single line of dialog, starting with a `|`. Every logic block in a line shares one scope that
+/// is created when the line is reached and thrown away once the line is done. A line can be given a
+/// label with `| @label`, so it can be jumped to with `dialog.label`.
+///
+/// Anything written before the first `<if>` is shared by every variant of the line. When a variant
+/// is picked, its text and divert replace the shared ones, and its tags and responses are added to
+/// the shared ones.
+#[derive(Serialize, Deserialize)]
+pub struct DialogLine {
+    label: Option<String>,
+    speaker: Option<LineSpeaker>,
+    content: LineContent,
+    variants: Vec<LineVariant>,
+    span: Span,
+}
+
+impl DialogLine {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn speaker(&self) -> Option<&LineSpeaker> {
+        self.speaker.as_ref()
+    }
+
+    pub fn content(&self) -> &LineContent {
+        &self.content
+    }
+
+    pub fn variants(&self) -> &Vec<LineVariant> {
+        &self.variants
+    }
+
+    /// All of the responses in the line, including the ones inside of variants.
+    pub fn responses(&self) -> impl Iterator<Item = &DialogResponse> {
+        self.content.responses.iter().chain(self.variants.iter().flat_map(|variant| variant.content.responses.iter()))
+    }
+
+    pub(super) fn responses_mut(&mut self) -> impl Iterator<Item = &mut DialogResponse> {
+        self.content.responses.iter_mut().chain(self.variants.iter_mut().flat_map(|variant| variant.content.responses.iter_mut()))
+    }
+
+    /// Every target of the responses and diverts in the line, including the ones inside of variants.
+    pub fn targets(&self) -> impl Iterator<Item = &DialogTarget> {
+        self.content.targets().chain(self.variants.iter().flat_map(|variant| variant.content.targets()))
+    }
+
+    pub(super) fn targets_mut(&mut self) -> impl Iterator<Item = &mut DialogTarget> {
+        self.content.targets_mut().chain(self.variants.iter_mut().flat_map(|variant| variant.content.targets_mut()))
+    }
+
+    /// Runs the logic blocks of this line in a child scope, picks the variant whose condition is met
+    /// and builds the line that is presented. Returns `None` if the line has no text for the variant
+    /// that was picked, and should be skipped.
+    pub fn present(&self, runtime: &mut LibrettoRuntime) -> Result<Option<PresentedLine>, LibrettoRuntimeError> {
+        runtime.push_scope([]);
+        let result = self.build(runtime);
+        runtime.pop_scope();
+        result
+    }
+
+    fn build(&self, runtime: &mut LibrettoRuntime) -> Result<Option<PresentedLine>, LibrettoRuntimeError> {
+        let selected = self.select(runtime)?;
+
+        let text = selected.and_then(|content| content.text.clone()).or_else(|| self.content.text.clone());
+        if text.is_none() && !self.variants.is_empty() {
+            return Ok(None);
+        }
+
+        let mut tags = self.content.tags.clone();
+        let mut candidates: Vec<&DialogResponse> = self.content.responses.iter().collect();
+        let mut divert = self.content.divert.as_ref();
+        if let Some(selected) = selected {
+            tags.extend(selected.tags.iter().cloned());
+            candidates.extend(selected.responses.iter());
+            divert = selected.divert.as_ref().or(divert);
+        }
+
+        let mut responses = Vec::new();
+        let mut choices = Vec::new();
+        for response in candidates {
+            let reason = match response.guard() {
+                Some(guard) => guard.check_guard(runtime)?,
+                None => None,
+            };
+            let available = reason.is_none();
+            let reason = reason.and_then(|reason| reason.as_string());
+            if !available && reason.is_none() {
+                continue;
+            }
+
+            let target = match response.target() {
+                ResponseTarget::Dialog(target) => Some(target.clone()),
+                ResponseTarget::Inline(_) => None,
+            };
+            choices.push(target.filter(|_| available));
+            responses.push(LibrettoResponse { text: response.text().to_string(), available, reason });
+        }
+
+        let speaker = match &self.speaker {
+            Some(speaker) => Some(speaker.resolve(runtime)?),
+            None => None,
+        };
+
+        let line = LibrettoLine {
+            speaker,
+            text: text.unwrap_or_default(),
+            tags,
+            responses,
+        };
+        Ok(Some(PresentedLine { line, choices, divert: divert.cloned() }))
+    }
+
+    /// Runs the shared logic, and then the logic of the first variant whose condition is met.
+    fn select(&self, runtime: &mut LibrettoRuntime) -> Result<Option<&LineContent>, LibrettoRuntimeError> {
+        self.content.run(runtime)?;
+        for variant in self.variants.iter() {
+            if variant.condition.evaluate(runtime)? == Lson::Bool(true) {
+                variant.content.run(runtime)?;
+                return Ok(Some(&variant.content));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoToken<'a>> for DialogLine {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>) -> bool {
+        if !queue.next_is(TokenOrdinal::Bar) {
+            return false;
+        }
+        queue.next_is(TokenOrdinal::Label);
+        queue.next_is([TokenOrdinal::Speaker, TokenOrdinal::SpeakerLogic]);
+        while queue.next_is([TokenOrdinal::Logic, TokenOrdinal::Quote, TokenOrdinal::Tag])
+            || DialogResponse::raw_check(queue)
+            || (queue.next_is(TokenOrdinal::Dash) && DialogTarget::raw_check(queue)) {}
+        true
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        let errors = compile_time.error_count();
+        queue.pop_if_next_is(TokenOrdinal::Bar)?;
+        let label = match queue.pop_if_next_is(TokenOrdinal::Label) {
+            Some(LibrettoToken::Label(label)) => Some(label),
+            _ => None,
+        };
+        let speaker = match queue.pop_if_next_is([TokenOrdinal::Speaker, TokenOrdinal::SpeakerLogic]) {
+            Some(LibrettoToken::Speaker(name)) => Some(LineSpeaker::Name(name, queue.last_span())),
+            Some(LibrettoToken::SpeakerLogic(mut inner)) => LogicExpr::parse_whole(&mut inner, compile_time).map(|expr| LineSpeaker::Expr(Box::new(expr))),
+            _ => None,
+        };
+
+        let mut content = LineContent::default();
+        let mut variants: Vec<LineVariant> = Vec::new();
+        loop {
+            queue.reset();
+            if DialogResponse::check(queue) {
+                let response = parse_ast!(DialogResponse, queue, compile_time);
+                variants.last_mut().map_or(&mut content, |variant| &mut variant.content).responses.push(response);
+                continue;
+            }
+
+            if queue.pop_if_next_is(TokenOrdinal::Dash).is_some() {
+                if !DialogTarget::check(queue) {
+                    push_syntax_error(queue, compile_time);
+                    return None;
+                }
+                let divert = parse_ast!(DialogTarget, queue, compile_time);
+                let span = divert.span();
+                let current = variants.last_mut().map_or(&mut content, |variant| &mut variant.content);
+                if current.divert.replace(divert).is_some() {
+                    compile_time.push_error(LibrettoCompileErrorKind::LineDivertRepeated, span);
+                }
+                continue;
+            }
+
+            let token = match queue.pop_if_next_is([TokenOrdinal::Logic, TokenOrdinal::Quote, TokenOrdinal::Tag]) {
+                Some(token) => token,
+                None => break,
+            };
+            let current = variants.last_mut().map_or(&mut content, |variant| &mut variant.content);
+            match token {
+                LibrettoToken::Logic(mut inner) => {
+                    if inner.next_is([LogicOrdinal::If, LogicOrdinal::Else]) {
+                        let Some(condition) = LogicCondition::parse_whole(&mut inner, compile_time) else {
+                            continue;
+                        };
+                        let in_order = if condition.is_if() {
+                            variants.is_empty()
+                        } else {
+                            variants.last().is_some_and(|variant| !variant.condition.is_else())
+                        };
+                        if !in_order {
+                            compile_time.push_error(LibrettoCompileErrorKind::VariantOutOfOrder, condition.span());
+                        }
+                        variants.push(LineVariant { condition, content: LineContent::default() });
+                    } else {
+                        current.blocks.extend(LogicBlock::parse(&mut inner, compile_time));
+                    }
+                }
+                LibrettoToken::Quote(quote) => {
+                    if current.text.replace(quote).is_some() {
+                        compile_time.push_error(LibrettoCompileErrorKind::LineTextRepeated, queue.last_span());
+                    }
+                }
+                LibrettoToken::Tag(tag) => current.tags.push(tag),
+                _ => return None,
+            }
+        }
+
+        // Errors inside the line have been reported, but a line with errors is not kept.
+        if compile_time.error_count() > errors {
+            return None;
+        }
+
+        Some(DialogLine { label, speaker, content, variants, span: start.to(queue.last_span()) })
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        compile_time.push_scope([]);
+        if let Some(speaker) = &self.speaker {
+            speaker.validate(compile_time);
+        }
+        self.content.validate(compile_time);
+        for variant in self.variants.iter() {
+            variant.condition.validate(compile_time);
+            compile_time.push_scope([]);
+            variant.content.validate(compile_time);
+            compile_time.pop_scope();
+        }
+        compile_time.pop_scope();
+        LsonType::None
+    }
+}
+
+//==================================================================================================
+//          Dialog Line Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lson::Lson,
+        parse::test_util::*,
+        runtime::LibrettoRuntime,
+    };
+
+    use super::*;
+
+    #[test]
+    fn check_dialog_line() {
+        check_dialog::<DialogLine>("| :Jake \"Hello there.\"", 3);
+        check_dialog::<DialogLine>("| <let test = 2;> \"Hello there.\" #angry", 4);
+        check_dialog::<DialogLine>("| \"Hello there.\"", 2);
+        check_dialog::<DialogLine>("| :Jake <if bar> \"A\" #angry <else> \"B\" -> \"Ok.\" shop", 10);
+    }
+
+    #[test]
+    fn parse_dialog_line() {
+        let ast = parse_dialog::<DialogLine>("| :Jake <let test = 2;> \"Hello there.\" #angry #fast");
+        assert!(matches!(ast.speaker(), Some(LineSpeaker::Name(name, _)) if name == "Jake"));
+        assert_eq!(ast.content().text(), Some("Hello there."));
+        assert_eq!(ast.content().tags(), &vec!["angry".to_string(), "fast".to_string()]);
+        assert_eq!(ast.content().blocks().len(), 1);
+        assert!(ast.variants().is_empty());
+    }
+
+    #[test]
+    fn parse_dialog_line_escapes() {
+        let ast = parse_dialog::<DialogLine>(r#"| :Jake "She said \"run\".\n\tNow! \u{2764}""#);
+        assert_eq!(ast.content().text(), Some("She said \"run\".\n\tNow! \u{2764}"));
+
+        let ast = parse_dialog::<DialogLine>(r##"| r#"C:\games "saved""# #path"##);
+        assert_eq!(ast.content().text(), Some(r#"C:\games "saved""#));
+        assert_eq!(ast.content().tags(), &vec!["path".to_string()]);
+
+        let ast = parse_dialog::<DialogLine>(r#"| r"\n stays""#);
+        assert_eq!(ast.content().text(), Some("\\n stays"));
+
+        let ast = parse_dialog::<DialogLine>(r###"| r##"a "#hash" in it"## #raw"###);
+        assert_eq!(ast.content().text(), Some(r##"a "#hash" in it"##));
+        assert_eq!(ast.content().tags(), &vec!["raw".to_string()]);
+
+        let mut tokens = <LibrettoToken as logos::Logos>::lexer(r#"| "bad \q""#);
+        assert_eq!(tokens.nth(1), Some(LibrettoToken::Error));
+    }
+
+    #[test]
+    fn parse_dialog_line_multiline() {
+        let source = "| :Jake \"\"\"\n        The road goes on.\n          \"Forever,\" he said.\n\n        The end.\\n\n    \"\"\" #long";
+        let ast = parse_dialog::<DialogLine>(source);
+        assert_eq!(ast.content().text(), Some("The road goes on.\n  \"Forever,\" he said.\n\nThe end.\n"));
+        assert_eq!(ast.content().tags(), &vec!["long".to_string()]);
+
+        let ast = parse_dialog::<DialogLine>("| \"\"\"One line.\"\"\"");
+        assert_eq!(ast.content().text(), Some("One line."));
+
+        let ast = parse_dialog::<DialogLine>(r#"| """He said "hi"""" #quoted"#);
+        assert_eq!(ast.content().text(), Some(r#"He said "hi""#));
+        assert_eq!(ast.content().tags(), &vec!["quoted".to_string()]);
+
+        let ast = parse_dialog::<DialogLine>(r#"| """Not \""" yet."""" #escaped"#);
+        assert_eq!(ast.content().text(), Some(r#"Not """ yet.""#));
+
+        let mut tokens = <LibrettoToken as logos::Logos>::lexer(r#"| """Never closed.""#);
+        assert_eq!(tokens.nth(1), Some(LibrettoToken::Error));
+    }
+
+    #[test]
+    fn parse_dialog_line_variants() {
+        let ast = parse_dialog::<DialogLine>("| :Jake #loud <if bar> \"A\" #angry <else if foo == 3.0> \"B\" <else> \"C\" -> \"Ok.\" shop");
+        assert_eq!(ast.content().tags(), &vec!["loud".to_string()]);
+        assert_eq!(ast.variants().len(), 3);
+        assert!(ast.variants()[0].condition().is_if());
+        assert_eq!(ast.variants()[0].content().text(), Some("A"));
+        assert_eq!(ast.variants()[0].content().tags(), &vec!["angry".to_string()]);
+        assert!(ast.variants()[2].condition().is_else());
+        assert_eq!(ast.variants()[2].content().responses().len(), 1);
+    }
+
+    #[test]
+    fn parse_dialog_line_variants_out_of_order() {
+        let mut compile_time = LibrettoCompiletime::default();
+        for source in ["| <else> \"A\"", "| <if bar> \"A\" <else> \"B\" <else> \"C\"", "| <if bar> \"A\" <if bar> \"B\""] {
+            let mut queue = LibrettoTokenQueue::from(<LibrettoToken as logos::Logos>::lexer(source));
+            assert!(DialogLine::checked_parse(&mut queue, &mut compile_time).is_none());
+        }
+    }
+
+    #[test]
+    fn validate_dialog_line() {
+        validate_dialog::<DialogLine>("| <let test = 2;> <test = 3>", 0);
+        validate_dialog::<DialogLine>("| <foo = 2.0> \"Hello\"", 0);
+        validate_dialog::<DialogLine>("| <test = 3> \"Hello\"", 1);
+        validate_dialog::<DialogLine>("| <if bar> \"A\" <else if foo> \"B\"", 1);
+        validate_dialog::<DialogLine>("| <if bar> <let test = 2;> \"A\" <else> <test = 3> \"B\"", 1);
+    }
+
+    #[test]
+    fn evaluate_dialog_line() {
+        let ast = parse_dialog::<DialogLine>("| :Jake <let test = 2.0;> <foo = test> \"Hello\" #angry");
+        let mut runtime = LibrettoRuntime::with_data([(String::from("foo"), Lson::Float(1.0))]);
+        let line = ast.present(&mut runtime).unwrap().unwrap().line;
+        assert_eq!(line.speaker, Some(LibrettoSpeaker::new("Jake", "Jake")));
+        assert_eq!(line.text, "Hello");
+        assert_eq!(line.tags, vec!["angry".to_string()]);
+        assert_eq!(runtime.get_data("foo"), Lson::Float(2.0));
+        assert!(!runtime.has_data("test"));
+    }
+
+    #[test]
+    fn evaluate_dialog_line_variants() {
+        let source = "| :Jake #loud <if bar> \"A\" #angry -> \"Why?\" why <else if foo == 3.0> \"B\" <else> \"C\" -> \"Ok.\" shop";
+        let ast = parse_dialog::<DialogLine>(source);
+
+        let mut runtime = LibrettoRuntime::with_data([(String::from("foo"), Lson::Float(1.0)), (String::from("bar"), Lson::Bool(true))]);
+        let PresentedLine { line, choices, .. } = ast.present(&mut runtime).unwrap().unwrap();
+        assert_eq!(line.text, "A");
+        assert_eq!(line.tags, vec!["loud".to_string(), "angry".to_string()]);
+        assert_eq!(line.responses.len(), 1);
+        assert_eq!(line.responses[0].text, "Why?");
+        assert_eq!(choices, vec![Some(DialogTarget::new("why", None))]);
+
+        let mut runtime = LibrettoRuntime::with_data([(String::from("foo"), Lson::Float(3.0)), (String::from("bar"), Lson::Bool(false))]);
+        let PresentedLine { line, choices, .. } = ast.present(&mut runtime).unwrap().unwrap();
+        assert_eq!(line.text, "B");
+        assert_eq!(line.tags, vec!["loud".to_string()]);
+        assert!(choices.is_empty());
+
+        let mut runtime = LibrettoRuntime::with_data([(String::from("foo"), Lson::Float(1.0)), (String::from("bar"), Lson::Bool(false))]);
+        let PresentedLine { line, choices, .. } = ast.present(&mut runtime).unwrap().unwrap();
+        assert_eq!(line.text, "C");
+        assert_eq!(choices, vec![Some(DialogTarget::new("shop", None))]);
+    }
+
+    #[test]
+    fn evaluate_dialog_line_guards() {
+        let source = "| \"Next!\" -> <if bar> \"I'm on the list.\" inside -> <if bar else \"You are not on the list.\"> \"Let me in.\" inside -> \"Leave.\" outside";
+        let ast = parse_dialog::<DialogLine>(source);
+
+        let mut runtime = LibrettoRuntime::with_data([(String::from("bar"), Lson::Bool(true))]);
+        let PresentedLine { line, choices, .. } = ast.present(&mut runtime).unwrap().unwrap();
+        assert_eq!(line.responses.len(), 3);
+        assert!(line.responses.iter().all(|response| response.available));
+        let inside = Some(DialogTarget::new("inside", None));
+        assert_eq!(choices, vec![inside.clone(), inside, Some(DialogTarget::new("outside", None))]);
+
+        let mut runtime = LibrettoRuntime::with_data([(String::from("bar"), Lson::Bool(false))]);
+        let PresentedLine { line, choices, .. } = ast.present(&mut runtime).unwrap().unwrap();
+        assert_eq!(line.responses.len(), 2);
+        assert_eq!(line.responses[0].text, "Let me in.");
+        assert!(!line.responses[0].available);
+        assert_eq!(line.responses[0].reason, Some("You are not on the list.".to_string()));
+        assert_eq!(choices, vec![None, Some(DialogTarget::new("outside", None))]);
+    }
+
+    #[test]
+    fn parse_dialog_line_divert() {
+        let ast = parse_dialog::<DialogLine>("| @greeting :Jake \"Hello.\" -- shop.greeting");
+        assert_eq!(ast.label(), Some("greeting"));
+        assert_eq!(ast.content().divert(), Some(&DialogTarget::new("shop", Some("greeting"))));
+
+        let ast = parse_dialog::<DialogLine>("| <if bar> \"A\" -- shop <else> \"B\" -> \"Ok.\" leave");
+        let targets: Vec<String> = ast.targets().map(|target| target.to_string()).collect();
+        assert_eq!(targets, vec!["shop", "leave"]);
+    }
+
+    #[test]
+    fn evaluate_dialog_line_divert() {
+        let ast = parse_dialog::<DialogLine>("| \"Hello.\" -- start <if bar> \"A\" -- shop <else> \"B\"");
+        let mut runtime = LibrettoRuntime::with_data([(String::from("bar"), Lson::Bool(true))]);
+        assert_eq!(ast.present(&mut runtime).unwrap().unwrap().divert, Some(DialogTarget::new("shop", None)));
+        runtime.insert_data("bar", Lson::Bool(false));
+        assert_eq!(ast.present(&mut runtime).unwrap().unwrap().divert, Some(DialogTarget::new("start", None)));
+    }
+
+    #[test]
+    fn parse_dialog_line_speaker_expr() {
+        let ast = parse_dialog::<DialogLine>("| :<name> \"Hello.\"");
+        assert!(matches!(ast.speaker(), Some(LineSpeaker::Expr(_))));
+    }
+
+    #[test]
+    fn validate_dialog_line_speaker() {
+        validate_dialog::<DialogLine>("| :<\"Jake\"> \"Hello.\"", 0);
+        validate_dialog::<DialogLine>("| :<foo> \"Hello.\"", 1);
+
+        let mut compile_time = LibrettoCompiletime::default();
+        compile_time.set_speakers([LibrettoSpeaker::new("jake", "Jake")].into());
+        for (source, errors) in [("| :jake \"Hello.\"", 0), ("| :todd \"Hello.\"", 1)] {
+            let mut queue = LibrettoTokenQueue::from(<LibrettoToken as logos::Logos>::lexer(source));
+            let ast = DialogLine::checked_parse(&mut queue, &mut compile_time).unwrap();
+            let before = compile_time.error_count();
+            ast.validate(&mut compile_time);
+            assert_eq!(compile_time.error_count() - before, errors, "{}", source);
+        }
+    }
+
+    #[test]
+    fn evaluate_dialog_line_speaker() {
+        let ast = parse_dialog::<DialogLine>("| :<name> \"Hello.\"");
+        let mut runtime = LibrettoRuntime::with_data([(String::from("name"), Lson::from("jake"))]);
+        let line = ast.present(&mut runtime).unwrap().unwrap().line;
+        assert_eq!(line.speaker, Some(LibrettoSpeaker::new("jake", "jake")));
+
+        runtime.set_speakers([LibrettoSpeaker::new("jake", "Jake").with_portrait("jake_happy").with_voice("jake_voice")].into());
+        let line = ast.present(&mut runtime).unwrap().unwrap().line;
+        let speaker = line.speaker.unwrap();
+        assert_eq!(speaker.name, "Jake");
+        assert_eq!(speaker.portrait, Some("jake_happy".to_string()));
+
+        runtime.insert_data("name", Lson::from("todd"));
+        assert!(matches!(ast.present(&mut runtime), Err(LibrettoRuntimeError { kind: LibrettoRuntimeErrorKind::SpeakerNotDefined(_), .. })));
+    }
+
+    #[test]
+    fn evaluate_dialog_line_without_match() {
+        let ast = parse_dialog::<DialogLine>("| <if bar> \"A\"");
+        let mut runtime = LibrettoRuntime::with_data([(String::from("bar"), Lson::Bool(false))]);
+        assert!(ast.present(&mut runtime).unwrap().is_none());
+    }
+}
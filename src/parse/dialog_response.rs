@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::LibrettoCompiletime,
+    lexer::{LibrettoToken, LibrettoTokenQueue, TokenOrdinal},
+    lson::LsonType,
+    span::Span,
+};
+
+use super::{dialog::Dialog, dialog_target::DialogTarget, logic_guard::LogicGuard, LibrettoParsable};
+
+//==================================================================================================
+//          Dialog Response
+//==================================================================================================
+
+/// Where a response leads to. Inline dialogs are moved out into the script when the script is
+/// parsed, so by the time a script runs every target is a named dialog.
+#[derive(Serialize, Deserialize)]
+pub enum ResponseTarget {
+    Dialog(DialogTarget),
+    Inline(Dialog),
+}
+
+impl ResponseTarget {
+    pub fn span(&self) -> Span {
+        match self {
+            ResponseTarget::Dialog(target) => target.span(),
+            ResponseTarget::Inline(dialog) => dialog.span(),
+        }
+    }
+}
+
+/// A response option, written as `-> "text" target` or `-> "text" { | ... }`. A response can be
+/// guarded with a logic block before its text, like `-> <if cond> "text" target`.
+#[derive(Serialize, Deserialize)]
+pub struct DialogResponse {
+    guard: Option<LogicGuard>,
+    text: String,
+    target: ResponseTarget,
+    span: Span,
+}
+
+impl DialogResponse {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn guard(&self) -> Option<&LogicGuard> {
+        self.guard.as_ref()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn target(&self) -> &ResponseTarget {
+        &self.target
+    }
+
+    pub(super) fn target_mut(&mut self) -> &mut ResponseTarget {
+        &mut self.target
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoToken<'a>> for DialogResponse {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>) -> bool {
+        if !queue.next_is(TokenOrdinal::Arrow) {
+            return false;
+        }
+        queue.next_is(TokenOrdinal::Logic);
+        if !queue.next_is(TokenOrdinal::Quote) {
+            return false;
+        }
+        DialogTarget::raw_check(queue) || Dialog::raw_check_body(queue)
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        queue.pop_if_next_is(TokenOrdinal::Arrow)?;
+        let guard = match queue.pop_if_next_is(TokenOrdinal::Logic) {
+            Some(LibrettoToken::Logic(mut inner)) => Some(LogicGuard::parse_whole(&mut inner, compile_time)?),
+            _ => None,
+        };
+        let text = if let Some(LibrettoToken::Quote(text)) = queue.pop_if_next_is(TokenOrdinal::Quote) {
+            text
+        } else {
+            return None;
+        };
+
+        queue.reset();
+        let target = if DialogTarget::check(queue) {
+            ResponseTarget::Dialog(DialogTarget::parse(queue, compile_time)?)
+        } else {
+            ResponseTarget::Inline(Dialog::parse_body(String::new(), queue, compile_time)?)
+        };
+
+        Some(DialogResponse { guard, text, target, span: start.to(queue.last_span()) })
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        if let Some(guard) = &self.guard {
+            guard.validate(compile_time);
+        }
+        if let ResponseTarget::Inline(dialog) = &self.target {
+            dialog.validate(compile_time);
+        }
+        LsonType::None
+    }
+}
+
+//==================================================================================================
+//          Dialog Response Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::test_util::*;
+
+    use super::*;
+
+    #[test]
+    fn check_dialog_response() {
+        check_dialog::<DialogResponse>("-> \"Show me your wares.\" shop", 3);
+        check_dialog::<DialogResponse>("-> \"Nevermind.\" { | \"Goodbye.\" }", 6);
+        check_dialog::<DialogResponse>("-> <if bar> \"I'm on the list.\" inside", 4);
+    }
+
+    #[test]
+    fn parse_dialog_response() {
+        let ast = parse_dialog::<DialogResponse>("-> \"Show me your wares.\" shop");
+        assert_eq!(ast.text(), "Show me your wares.");
+        assert!(matches!(ast.target(), ResponseTarget::Dialog(target) if target.dialog() == "shop"));
+
+        let ast = parse_dialog::<DialogResponse>("-> \"Say hello.\" shop.greeting");
+        assert!(matches!(ast.target(), ResponseTarget::Dialog(target) if target.label() == Some("greeting")));
+
+        let ast = parse_dialog::<DialogResponse>("-> \"Nevermind.\" { | \"Goodbye.\" }");
+        assert!(matches!(ast.target(), ResponseTarget::Inline(dialog) if dialog.lines().len() == 1));
+        assert!(ast.guard().is_none());
+
+        let ast = parse_dialog::<DialogResponse>("-> <if bar else \"You are not on the list.\"> \"I'm on the list.\" inside");
+        assert!(ast.guard().is_some_and(|guard| guard.reason().is_some()));
+    }
+
+    #[test]
+    fn validate_dialog_response() {
+        validate_dialog::<DialogResponse>("-> \"Nevermind.\" { | <foo = 2.0> \"Goodbye.\" }", 0);
+        validate_dialog::<DialogResponse>("-> \"Nevermind.\" { | <test = 2> \"Goodbye.\" }", 1);
+        validate_dialog::<DialogResponse>("-> <if bar> \"I'm on the list.\" inside", 0);
+        validate_dialog::<DialogResponse>("-> <if foo else 3> \"I'm on the list.\" inside", 2);
+    }
+}
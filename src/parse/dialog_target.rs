@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::{
+    compiler::{LibrettoCompileErrorKind, LibrettoCompiletime},
+    lexer::{LibrettoToken, LibrettoTokenQueue, TokenOrdinal},
+    lson::LsonType,
+    runtime::dialog::DialogCursor,
+    span::Span,
+};
+
+use super::{dialog::Dialog, LibrettoParsable};
+
+//==================================================================================================
+//          Dialog Target
+//==================================================================================================
+
+/// Somewhere a response or a divert can lead to. A target is written as `name` or `dialog.label`.
+/// A plain `name` can either be a dialog, or a labelled line in the dialog the target is written in.
+///
+/// A target written as `call name` is a call: once the dialog it leads to runs out of lines, the
+/// runtime returns to the line after the one that made the call.
+///
+/// Targets are resolved once the whole script is parsed, so the runtime never has to look them up.
+/// Two targets are equal when they are written the same way, no matter where they were written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogTarget {
+    dialog: String,
+    label: Option<String>,
+    call: bool,
+    resolved: Option<DialogCursor>,
+    span: Span,
+}
+
+impl PartialEq for DialogTarget {
+    fn eq(&self, other: &Self) -> bool {
+        self.dialog == other.dialog && self.label == other.label && self.call == other.call
+    }
+}
+
+impl DialogTarget {
+    pub fn new(dialog: &str, label: Option<&str>) -> Self {
+        DialogTarget {
+            dialog: dialog.to_string(),
+            label: label.map(|label| label.to_string()),
+            call: false,
+            resolved: None,
+            span: Span::default(),
+        }
+    }
+
+    /// Gives the target the span it was written at.
+    pub fn at(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Makes this target a call, which returns once the dialog it leads to is done.
+    pub fn into_call(mut self) -> Self {
+        self.call = true;
+        self
+    }
+
+    /// Parses a target from how it is written in a script, like `shop` or `shop.greeting`.
+    pub fn from_path(path: &str) -> Self {
+        match path.split_once('.') {
+            Some((dialog, label)) => DialogTarget::new(dialog, Some(label)),
+            None => DialogTarget::new(path, None),
+        }
+    }
+
+    pub fn dialog(&self) -> &str {
+        &self.dialog
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn is_call(&self) -> bool {
+        self.call
+    }
+
+    pub(crate) fn resolved(&self) -> Option<DialogCursor> {
+        self.resolved
+    }
+
+    /// Looks for the line this target leads to. `from` is the index of the dialog the target is
+    /// written in, which is where a plain name is looked for as a label.
+    pub(crate) fn find(&self, dialogs: &[Dialog], from: Option<usize>) -> Result<DialogCursor, LibrettoCompileErrorKind> {
+        let named = dialogs.iter().enumerate().filter(|(_, dialog)| dialog.name() == self.dialog);
+
+        let candidates: Vec<DialogCursor> = match &self.label {
+            Some(label) => named
+                .flat_map(|(index, dialog)| dialog.label_lines(label).map(move |line| DialogCursor { dialog: index, line }))
+                .collect(),
+            None => {
+                let mut candidates: Vec<DialogCursor> = named.map(|(index, _)| DialogCursor { dialog: index, line: 0 }).collect();
+                if let Some(from) = from {
+                    candidates.extend(dialogs[from].label_lines(&self.dialog).map(|line| DialogCursor { dialog: from, line }));
+                }
+                candidates
+            }
+        };
+
+        match candidates.as_slice() {
+            [cursor] => Ok(*cursor),
+            [] => Err(LibrettoCompileErrorKind::TargetNotDefined(self.to_string())),
+            _ => Err(LibrettoCompileErrorKind::TargetAmbiguous(self.to_string())),
+        }
+    }
+
+    pub(super) fn set_resolved(&mut self, resolved: Option<DialogCursor>) {
+        self.resolved = resolved;
+    }
+}
+
+impl Display for DialogTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "{}.{}", self.dialog, label),
+            None => write!(f, "{}", self.dialog),
+        }
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoToken<'a>> for DialogTarget {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>) -> bool {
+        queue.next_is(TokenOrdinal::Call);
+        queue.next_is([TokenOrdinal::Identifier, TokenOrdinal::Path])
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>, _compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        let call = queue.pop_if_next_is(TokenOrdinal::Call).is_some();
+        let target = match queue.pop_if_next_is([TokenOrdinal::Identifier, TokenOrdinal::Path]) {
+            Some(LibrettoToken::Identifier(path)) | Some(LibrettoToken::Path(path)) => DialogTarget::from_path(&path),
+            _ => return None,
+        };
+        let target = target.at(start.to(queue.last_span()));
+        Some(if call { target.into_call() } else { target })
+    }
+
+    fn validate(&self, _compile_time : &mut LibrettoCompiletime) -> LsonType {
+        LsonType::None
+    }
+}
+
+//==================================================================================================
+//          Dialog Target Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::test_util::*;
+
+    use super::*;
+
+    #[test]
+    fn check_dialog_target() {
+        check_dialog::<DialogTarget>("shop", 1);
+        check_dialog::<DialogTarget>("shop.greeting", 1);
+        check_dialog::<DialogTarget>("call barter", 2);
+    }
+
+    #[test]
+    fn parse_dialog_target() {
+        let ast = parse_dialog::<DialogTarget>("shop");
+        assert_eq!(ast, DialogTarget::new("shop", None));
+
+        let ast = parse_dialog::<DialogTarget>("shop.greeting");
+        assert_eq!(ast, DialogTarget::new("shop", Some("greeting")));
+        assert_eq!(ast.to_string(), "shop.greeting");
+        assert!(!ast.is_call());
+
+        let ast = parse_dialog::<DialogTarget>("call barter.menu");
+        assert_eq!(ast, DialogTarget::new("barter", Some("menu")).into_call());
+        assert_eq!(ast.to_string(), "barter.menu");
+        assert_eq!(ast.span(), Span::new(0, 16, 1, 1));
+    }
+}
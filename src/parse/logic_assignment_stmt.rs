@@ -1,12 +1,21 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::{lexer::{LibrettoTokenQueue, LibrettoLogicToken, LogicOrdinal}, lson::{LsonType, Lson}, parse_ast, compiler::{LibrettoCompileError, LibrettoCompiletime}, runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult}};
+use crate::{lexer::{LibrettoTokenQueue, LibrettoLogicToken, LogicOrdinal}, lson::{LsonType, Lson}, parse_ast, compiler::{LibrettoCompileErrorKind, LibrettoCompiletime}, runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult, LibrettoRuntimeErrorKind}, span::Span};
 
 use super::{logic_equality_expr::LogicEqualityExpr, LibrettoParsable, logic_expr::LogicExpr};
 
+#[derive(Serialize, Deserialize)]
 pub struct LogicAssignmentStatement {
     ident: String,
-    value : LogicExpr
+    value : LogicExpr,
+    span: Span,
+}
+
+impl LogicAssignmentStatement {
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
 impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicAssignmentStatement {
@@ -17,6 +26,7 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicAssignmentStatement
     }
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         let ident = if let Some(LibrettoLogicToken::Identifier(ident)) = queue.pop() {
             ident
         } else {
@@ -24,19 +34,26 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicAssignmentStatement
         };
         queue.pop();
         let value = parse_ast!(LogicExpr, queue, compile_time);
-        Some(LogicAssignmentStatement{ident, value})
+        Some(LogicAssignmentStatement{ident, value, span: start.to(queue.last_span())})
     }
 
     fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
-        let desired_type = compile_time.get_variable_type(&self.ident);
         let value_type = self.value.validate(compile_time);
 
-        if desired_type == LsonType::None {
-            compile_time.push_error(LibrettoCompileError::AssignmentWithUndeclaredVariable(self.ident.clone()))
+        // The first assignment to a variable declared without a type decides its type.
+        if compile_time.is_variable_pending(&self.ident) {
+            compile_time.infer_variable_type(&self.ident, value_type, self.span);
+            return value_type;
         }
 
-        if desired_type != value_type {
-            compile_time.push_error(LibrettoCompileError::AssignmentWithInvalidType(self.ident.clone()));
+        let desired_type = compile_time.get_variable_type(&self.ident);
+        if desired_type == LsonType::None {
+            compile_time.push_error(LibrettoCompileErrorKind::AssignmentWithUndeclaredVariable(self.ident.clone()), self.span);
+        } else if !desired_type.accepts(value_type) {
+            let error = LibrettoCompileErrorKind::AssignmentWithInvalidType(self.ident.clone(), desired_type.to_string(), value_type.to_string())
+                .at(self.span)
+                .with_origin(compile_time.variable_origin(&self.ident));
+            compile_time.push(error);
         }
 
         desired_type
@@ -45,7 +62,15 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicAssignmentStatement
 
 impl LibrettoEvaluator for LogicAssignmentStatement {
     fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
-        todo!()
+        let value = self.value.evaluate(runtime)?;
+        // A variable checked as a float always holds one, even before it is assigned, since a
+        // typed `let` without a value starts at the default of its type.
+        let value = if runtime.get_data(&self.ident).is_f64() { value.coerce_to(LsonType::Float) } else { value };
+        if runtime.replace_data(&self.ident, value.clone()) {
+            Ok(value)
+        } else {
+            Err(LibrettoRuntimeErrorKind::VariableNotDefined(self.ident.clone()).at(self.span))
+        }
     }
 }
 
@@ -73,8 +98,15 @@ mod tests {
 
     #[test]
     fn validate_assign_stmt() {
-        validate_expr::<LogicAssignmentStatement>("test = 2", 2, LsonType::None);
+        validate_expr::<LogicAssignmentStatement>("test = 2", 1, LsonType::None);
+        validate_expr::<LogicAssignmentStatement>("foo = 2", 0, LsonType::Float);
         validate_expr::<LogicAssignmentStatement>("bar = 2", 1, LsonType::Bool);
         validate_expr::<LogicAssignmentStatement>("foo = 2.0", 0, LsonType::Float);
     }
+
+    #[test]
+    fn evaluate_assign_stmt() {
+        let rt = evaluate_expr::<LogicAssignmentStatement>("foo = 4.0", Lson::Float(4.0));
+        assert_eq!(rt.get_data("foo"), Lson::Float(4.0));
+    }
 }
\ No newline at end of file
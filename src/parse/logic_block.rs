@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::LibrettoCompiletime,
+    lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal},
+    lson::{Lson, LsonType},
+    parse_ast,
+    runtime::{LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeResult},
+    span::Span,
+};
+
+use super::{logic_stmt::LogicStatement, push_syntax_error, LibrettoParsable};
+
+//==================================================================================================
+//          Logic Block
+//==================================================================================================
+
+/// The contents of a single `<...>` block. A block is a list of statements that are run in order.
+/// Where the block lives decides which scope it runs in: blocks outside of a dialog are global and
+/// run into the root scope, while blocks inside of a line share a scope that only lives for that line.
+/// A statement that doesn't parse is reported and skipped up to the next `;`.
+#[derive(Serialize, Deserialize)]
+pub struct LogicBlock {
+    statements: Vec<LogicStatement>,
+    span: Span,
+}
+
+impl LogicBlock {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn statements(&self) -> &Vec<LogicStatement> {
+        &self.statements
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicBlock {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
+        while !queue.is_exhausted() {
+            if !LogicStatement::raw_check(queue) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        let mut statements = Vec::new();
+        queue.reset();
+        while !queue.is_exhausted() {
+            queue.clear_failure();
+            if LogicStatement::check(queue) {
+                statements.push(parse_ast!(LogicStatement, queue, compile_time));
+            } else {
+                // Skip the broken statement, so the ones after it are still parsed and checked.
+                push_syntax_error(queue, compile_time);
+                queue.skip_until(LogicOrdinal::Semicolon);
+                queue.pop();
+            }
+            queue.reset();
+        }
+        Some(LogicBlock { statements, span: start.to(queue.last_span()) })
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        for statement in self.statements.iter() {
+            statement.validate(compile_time);
+        }
+        LsonType::None
+    }
+}
+
+impl LibrettoEvaluator for LogicBlock {
+    fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
+        for statement in self.statements.iter() {
+            statement.evaluate(runtime)?;
+        }
+        Ok(Lson::None)
+    }
+}
+
+//==================================================================================================
+//          Logic Block Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use logos::Logos;
+
+    use crate::{
+        compiler::LibrettoCompileErrorKind,
+        lson::{Lson, LsonType},
+        parse::test_util::*,
+    };
+
+    use super::*;
+
+    #[test]
+    fn check_logic_block() {
+        check_expr::<LogicBlock>("let test = 2;", 5);
+        check_expr::<LogicBlock>("let test = 2; test = 3; foo = 2.0", 12);
+    }
+
+    #[test]
+    fn parse_logic_block() {
+        let ast = parse_expr::<LogicBlock>("let test = 2; test = 3; foo = 2.0");
+        assert_eq!(ast.statements().len(), 3);
+
+        let ast = parse_expr::<LogicBlock>("");
+        assert!(ast.statements().is_empty());
+    }
+
+    #[test]
+    fn recover_logic_block() {
+        let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer("let test = 2; let = 3; test = ; foo = 2.0"));
+        let mut compile_time = LibrettoCompiletime::default();
+        let ast = LogicBlock::parse(&mut queue, &mut compile_time).unwrap();
+        assert_eq!(ast.statements().len(), 3);
+        assert_eq!(compile_time.error_count(), 2);
+        assert_eq!(compile_time.errors()[0].kind, LibrettoCompileErrorKind::UnexpectedToken("a name".to_string(), "`=`".to_string()));
+        assert_eq!(compile_time.errors()[0].span, Span::new(18, 19, 1, 19));
+    }
+
+    #[test]
+    fn validate_logic_block() {
+        validate_expr::<LogicBlock>("let test = 2; test = 3;", 0, LsonType::None);
+        validate_expr::<LogicBlock>("let test = 2; test = false;", 1, LsonType::None);
+        validate_expr::<LogicBlock>("let test; test = 2; let other = test + 1;", 0, LsonType::None);
+        validate_expr::<LogicBlock>("let test; let other = test;", 1, LsonType::None);
+    }
+
+    #[test]
+    fn infer_logic_block() {
+        let source = "let test; test = 2; test = false;";
+        let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer(source));
+        let mut compile_time = LibrettoCompiletime::default();
+        let ast = LogicBlock::checked_parse(&mut queue, &mut compile_time).unwrap();
+        ast.validate(&mut compile_time);
+        assert_eq!(compile_time.get_variable_type("test"), LsonType::Int);
+        assert_eq!(compile_time.error_count(), 1);
+
+        let error = &compile_time.errors()[0];
+        assert_eq!(error.kind, LibrettoCompileErrorKind::AssignmentWithInvalidType("test".to_string(), "int".to_string(), "bool".to_string()));
+        assert_eq!(error.span, Span::new(20, 32, 1, 21));
+        assert_eq!(error.origin, Some(Span::new(10, 18, 1, 11)));
+
+        let mut compile_time = LibrettoCompiletime::default();
+        compile_time.push_scope(HashMap::new());
+        let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer("let test;"));
+        LogicBlock::checked_parse(&mut queue, &mut compile_time).unwrap().validate(&mut compile_time);
+        compile_time.pop_scope();
+        assert_eq!(compile_time.errors()[0].kind, LibrettoCompileErrorKind::TypeNotExplicit("test".to_string()));
+    }
+
+    #[test]
+    fn evaluate_logic_block() {
+        let rt = evaluate_expr::<LogicBlock>("let test = 2; test = 3; foo = 2.5", Lson::None);
+        assert_eq!(rt.get_data("test"), Lson::Int(3));
+        assert_eq!(rt.get_data("foo"), Lson::Float(2.5));
+
+        let rt = evaluate_expr::<LogicBlock>("foo = 3", Lson::None);
+        assert_eq!(rt.get_data("foo"), Lson::Float(3.0));
+
+        let rt = evaluate_expr::<LogicBlock>("let test : float; test = 2;", Lson::None);
+        assert!(matches!(rt.get_data("test"), Lson::Float(value) if value == 2.0));
+    }
+}
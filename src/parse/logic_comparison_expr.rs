@@ -1,18 +1,32 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
-use crate::compiler::{LibrettoCompileError, LibrettoCompiletime};
+use crate::compiler::{LibrettoCompileErrorKind, LibrettoCompiletime};
 use crate::lexer::{LibrettoLogicToken, LogicOrdinal };
 use crate::lson::{LsonType, Lson};
-use crate::runtime::{LibrettoEvaluator, LibrettoRuntimeResult};
+use crate::runtime::{LibrettoEvaluator, LibrettoRuntimeErrorKind, LibrettoRuntimeResult};
+use crate::span::Span;
 use super::{logic_term_expr::LogicTermExpr, LibrettoParsable};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct LogicComparisonExpr {
     lhs : LogicTermExpr,
-    rhs : Vec<(ComparisonOperator, LogicTermExpr)>
+    rhs : Vec<(ComparisonOperator, LogicTermExpr)>,
+    span: Span,
 }
 
-#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+impl LogicComparisonExpr {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.lhs.is_constant() && self.rhs.iter().all(|(_, rhs)| rhs.is_constant())
+    }
+}
+
+#[derive(PartialEq, Debug, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum ComparisonOperator {
     LessThan,
     GreaterThan,
@@ -39,6 +53,7 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicComparisonExpr {
     }
 
     fn parse(queue: &mut crate::lexer::LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         let lhs = LogicTermExpr::parse(queue, compile_time).unwrap();
         let mut rhs = Vec::new();
         
@@ -64,20 +79,24 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicComparisonExpr {
             }
         }
         
-        Some(LogicComparisonExpr { lhs, rhs })
+        Some(LogicComparisonExpr { lhs, rhs, span: start.to(queue.last_span()) })
     }
 
     fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
         let mut lhs_type = self.lhs.validate(compile_time);
+        let mut lhs_span = self.lhs.span();
 
         if !self.rhs.is_empty() {
+            // Each operator compares the values on either side of it, so that pair is what a
+            // mismatch is reported on.
             for (op, rhs) in &self.rhs {
                 let rhs_type = rhs.validate(compile_time);
                 if let LsonType::None = lhs_type.get_comparison_type(rhs_type) {
-                    compile_time.push_error(LibrettoCompileError::InvalidOperationError(lhs_type.to_string(), op.to_string(), rhs_type.to_string()));
+                    compile_time.push_error(LibrettoCompileErrorKind::InvalidOperationError(lhs_type.to_string(), op.to_string(), rhs_type.to_string()), lhs_span.to(rhs.span()));
                     return LsonType::None
                 }
                 lhs_type = rhs_type;
+                lhs_span = rhs.span();
             }
             LsonType::Bool
         } else {
@@ -86,8 +105,15 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicComparisonExpr {
     }
 }
 
-fn get_comaprison_type(lhs : &LsonType, op : &ComparisonOperator, rhs : &LsonType) -> LsonType {
-    lhs.get_comparison_type(*rhs)
+impl ComparisonOperator {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            ComparisonOperator::LessThan => ordering.is_lt(),
+            ComparisonOperator::GreaterThan => ordering.is_gt(),
+            ComparisonOperator::LessThanEqualTo => ordering.is_le(),
+            ComparisonOperator::GreaterThanEqualTo => ordering.is_ge(),
+        }
+    }
 }
 
 impl LibrettoEvaluator for LogicComparisonExpr {
@@ -97,12 +123,11 @@ impl LibrettoEvaluator for LogicComparisonExpr {
         if !self.rhs.is_empty() {
             for (op, rhs) in &self.rhs {
                 let v2 = rhs.evaluate(runtime)?;
-                match op {
-                    ComparisonOperator::LessThan => if !(v1 < v2) { cardnality = false },
-                    ComparisonOperator::GreaterThan => if !(v1 > v2) { cardnality = false },
-                    ComparisonOperator::LessThanEqualTo => if !(v1 <= v2) { cardnality = false },
-                    ComparisonOperator::GreaterThanEqualTo => if !(v1 >= v2) { cardnality = false },
-                };
+                let ordering = v1.compare(&v2)
+                    .map_err(|(left, right)| LibrettoRuntimeErrorKind::Incomparable(left.to_string(), right.to_string()).at(self.span))?;
+                if !op.holds(ordering) {
+                    cardnality = false;
+                }
                 v1 = v2;
             }
             Ok(Lson::Bool(cardnality))
@@ -119,9 +144,13 @@ impl LibrettoEvaluator for LogicComparisonExpr {
 #[cfg(test)]
 mod tests {
 
+    use logos::Logos;
+
     use crate::{
+        lexer::LibrettoTokenQueue,
         lson::LsonType,
         parse::test_util::*,
+        runtime::LibrettoRuntime,
     };
 
     use super::*;
@@ -140,7 +169,7 @@ mod tests {
     fn parse_factor_expr() {
         let ast = parse_expr::<LogicComparisonExpr>("2+4*6 >= 5");
         assert_eq!(ast.lhs, parse_expr::<LogicTermExpr>("2+4*6"));
-        assert_eq!(ast.rhs, vec![(ComparisonOperator::GreaterThanEqualTo, parse_expr::<LogicTermExpr>("5"))]);
+        assert_eq!(ast.rhs, vec![(ComparisonOperator::GreaterThanEqualTo, parse_expr::<LogicTermExpr>("         5"))]);
 
         let ast = parse_expr::<LogicComparisonExpr>("10 < 15 < 20");
         assert_eq!(ast.rhs.len(), 2);
@@ -151,6 +180,15 @@ mod tests {
         validate_expr::<LogicComparisonExpr>("!false", 0, LsonType::Bool);
         validate_expr::<LogicComparisonExpr>("2 * 2", 0, LsonType::Int);
         validate_expr::<LogicComparisonExpr>("10 < 15 < 20", 0, LsonType::Bool);
+        validate_expr::<LogicComparisonExpr>("\"a\" < \"b\"", 0, LsonType::Bool);
+        validate_expr::<LogicComparisonExpr>("\"a\" < 2", 1, LsonType::None);
+        validate_expr::<LogicComparisonExpr>("{ a : 1 } < { a : 2 }", 1, LsonType::None);
+        validate_expr::<LogicComparisonExpr>("none <= none", 0, LsonType::Bool);
+
+        let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer("1 < 2 < \"a\""));
+        let mut compile_time = LibrettoCompiletime::default();
+        LogicComparisonExpr::checked_parse(&mut queue, &mut compile_time).unwrap().validate(&mut compile_time);
+        assert_eq!(compile_time.errors()[0].span, Span::new(4, 11, 1, 5));
     }
 
     #[test]
@@ -162,4 +200,22 @@ mod tests {
         evaluate_expr::<LogicComparisonExpr>("2*2+2*2", Lson::Int(8));
         evaluate_expr::<LogicComparisonExpr>("10 < 15 < 20 > 15 > 10", Lson::Bool(true));
     }
+
+    #[test]
+    fn eval_comparison_promotion() {
+        evaluate_expr::<LogicComparisonExpr>("2 < 2.5", Lson::Bool(true));
+        evaluate_expr::<LogicComparisonExpr>("2.5 > 2", Lson::Bool(true));
+        evaluate_expr::<LogicComparisonExpr>("2 >= 2.0", Lson::Bool(true));
+        evaluate_expr::<LogicComparisonExpr>("\"apple\" < \"banana\"", Lson::Bool(true));
+        evaluate_expr::<LogicComparisonExpr>("[1, 2] < [1, 3]", Lson::Bool(true));
+        evaluate_expr::<LogicComparisonExpr>("[{ a : 1 }] >= [{ a : 1.0 }]", Lson::Bool(true));
+        evaluate_expr::<LogicComparisonExpr>("none >= none", Lson::Bool(true));
+    }
+
+    #[test]
+    fn eval_comparison_incomparable() {
+        let ast = parse_expr::<LogicComparisonExpr>("[1] < [\"a\"]");
+        let error = ast.evaluate(&mut LibrettoRuntime::with_data([])).unwrap_err();
+        assert_eq!(error.kind, LibrettoRuntimeErrorKind::Incomparable("int".to_string(), "string".to_string()));
+    }
 }
\ No newline at end of file
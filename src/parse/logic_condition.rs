@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::{LibrettoCompileErrorKind, LibrettoCompileWarningKind, LibrettoCompiletime},
+    lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal},
+    lson::{Lson, LsonType},
+    parse_ast,
+    runtime::{LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeErrorKind, LibrettoRuntimeResult},
+    span::Span,
+};
+
+use super::{logic_expr::LogicExpr, LibrettoParsable};
+
+//==================================================================================================
+//          Logic Condition
+//==================================================================================================
+
+/// The header of a conditional variant in a line, like `<if cond>`, `<else if cond>` or `<else>`.
+/// Unlike an if statement, a condition has no body. What follows it in the line is the body.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum LogicCondition {
+    If(LogicExpr, Span),
+    ElseIf(LogicExpr, Span),
+    Else(Span),
+}
+
+impl LogicCondition {
+    pub fn is_else(&self) -> bool {
+        matches!(self, LogicCondition::Else(_))
+    }
+
+    pub fn is_if(&self) -> bool {
+        matches!(self, LogicCondition::If(..))
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            LogicCondition::If(_, span) | LogicCondition::ElseIf(_, span) | LogicCondition::Else(span) => *span,
+        }
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicCondition {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
+        if queue.next_is(LogicOrdinal::If) {
+            LogicExpr::raw_check(queue) && queue.is_exhausted()
+        } else if queue.next_is(LogicOrdinal::Else) {
+            if queue.next_is(LogicOrdinal::If) {
+                LogicExpr::raw_check(queue) && queue.is_exhausted()
+            } else {
+                queue.is_exhausted()
+            }
+        } else {
+            false
+        }
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        if queue.pop_if_next_is(LogicOrdinal::If).is_some() {
+            let expr = parse_ast!(LogicExpr, queue, compile_time);
+            return Some(LogicCondition::If(expr, start.to(queue.last_span())));
+        }
+
+        queue.pop_if_next_is(LogicOrdinal::Else)?;
+        if queue.pop_if_next_is(LogicOrdinal::If).is_some() {
+            let expr = parse_ast!(LogicExpr, queue, compile_time);
+            Some(LogicCondition::ElseIf(expr, start.to(queue.last_span())))
+        } else {
+            Some(LogicCondition::Else(start))
+        }
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        match self {
+            LogicCondition::If(expr, _) | LogicCondition::ElseIf(expr, _) => {
+                let expr_type = expr.validate(compile_time);
+                if expr_type != LsonType::Bool {
+                    compile_time.push_error(LibrettoCompileErrorKind::ConditionNotBool(expr_type.to_string()), expr.span());
+                } else if expr.is_constant() {
+                    compile_time.push_warning(LibrettoCompileWarningKind::ConstantCondition, expr.span());
+                }
+            }
+            LogicCondition::Else(_) => {}
+        }
+        LsonType::Bool
+    }
+}
+
+impl LibrettoEvaluator for LogicCondition {
+    fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
+        match self {
+            LogicCondition::If(expr, _) | LogicCondition::ElseIf(expr, _) => {
+                let value = expr.evaluate(runtime)?;
+                if value.is_bool() {
+                    Ok(value)
+                } else {
+                    Err(LibrettoRuntimeErrorKind::ConditionNotBool(value.get_type().to_string()).at(expr.span()))
+                }
+            }
+            LogicCondition::Else(_) => Ok(Lson::Bool(true)),
+        }
+    }
+}
+
+//==================================================================================================
+//          Logic Condition Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lson::{Lson, LsonType},
+        parse::test_util::*,
+    };
+
+    use super::*;
+
+    #[test]
+    fn check_logic_condition() {
+        check_expr::<LogicCondition>("if bar", 2);
+        check_expr::<LogicCondition>("else if 10 < 15", 5);
+        check_expr::<LogicCondition>("else", 1);
+    }
+
+    #[test]
+    fn parse_logic_condition() {
+        let ast = parse_expr::<LogicCondition>("if bar");
+        assert!(matches!(&ast, LogicCondition::If(expr, _) if expr.span() == Span::new(3, 6, 1, 4)));
+        assert_eq!(ast.span(), Span::new(0, 6, 1, 1));
+
+        let ast = parse_expr::<LogicCondition>("else if foo > 2");
+        assert!(matches!(&ast, LogicCondition::ElseIf(expr, _) if expr.span() == Span::new(8, 15, 1, 9)));
+
+        let ast = parse_expr::<LogicCondition>("else");
+        assert_eq!(ast, LogicCondition::Else(Span::new(0, 4, 1, 1)));
+    }
+
+    #[test]
+    fn validate_logic_condition() {
+        validate_expr::<LogicCondition>("if bar", 0, LsonType::Bool);
+        validate_expr::<LogicCondition>("else if foo", 1, LsonType::Bool);
+        validate_expr::<LogicCondition>("else", 0, LsonType::Bool);
+    }
+
+    #[test]
+    fn evaluate_logic_condition() {
+        evaluate_expr::<LogicCondition>("if foo > 3", Lson::Bool(false));
+        evaluate_expr::<LogicCondition>("else if bar", Lson::Bool(true));
+        evaluate_expr::<LogicCondition>("else", Lson::Bool(true));
+    }
+}
@@ -1,19 +1,32 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::compiler::{LibrettoCompiletime, LibrettoCompileError};
+use crate::compiler::{LibrettoCompiletime, LibrettoCompileErrorKind};
 use crate::lexer::{LibrettoLogicToken, LogicOrdinal, LibrettoTokenQueue };
 use crate::lson::{LsonType, Lson};
 use crate::runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult};
+use crate::span::Span;
 use super::logic_comparison_expr::LogicComparisonExpr;
 use super::{logic_term_expr::LogicTermExpr, LibrettoParsable};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct LogicEqualityExpr {
     lhs : LogicComparisonExpr,
-    rhs : Vec<(EqualityOperator, LogicComparisonExpr)>
+    rhs : Vec<(EqualityOperator, LogicComparisonExpr)>,
+    span: Span,
 }
 
-#[derive(PartialEq, Debug, Eq, Clone, Copy)]
+impl LogicEqualityExpr {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.lhs.is_constant() && self.rhs.iter().all(|(_, rhs)| rhs.is_constant())
+    }
+}
+
+#[derive(PartialEq, Debug, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum EqualityOperator {
     EqualTo,
     NotEqualTo
@@ -36,6 +49,7 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicEqualityExpr {
     }
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         let lhs = LogicComparisonExpr::parse(queue, compile_time).unwrap();
         let mut rhs = Vec::new();
 
@@ -59,20 +73,24 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicEqualityExpr {
             }
         }
 
-        Some(LogicEqualityExpr { lhs, rhs })
+        Some(LogicEqualityExpr { lhs, rhs, span: start.to(queue.last_span()) })
     }
 
     fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
         let mut lhs_type = self.lhs.validate(compile_time);
+        let mut lhs_span = self.lhs.span();
 
         if !self.rhs.is_empty() {
+            // Each operator compares the values on either side of it, so that pair is what a
+            // mismatch is reported on.
             for (op, rhs) in &self.rhs {
                 let rhs_type = rhs.validate(compile_time);
-                if let LsonType::None = lhs_type.get_comparison_type(rhs_type) {
-                    compile_time.push_error(LibrettoCompileError::InvalidOperationError(lhs_type.to_string(), op.to_string(), rhs_type.to_string()));
+                if let LsonType::None = lhs_type.get_equality_type(rhs_type) {
+                    compile_time.push_error(LibrettoCompileErrorKind::InvalidOperationError(lhs_type.to_string(), op.to_string(), rhs_type.to_string()), lhs_span.to(rhs.span()));
                     return LsonType::None
                 }
                 lhs_type = rhs_type;
+                lhs_span = rhs.span();
             }
             LsonType::Bool
         } else {
@@ -81,10 +99,6 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicEqualityExpr {
     }
 }
 
-fn get_equality_type(lhs : &LsonType, op : &EqualityOperator, rhs : &LsonType) -> LsonType {
-    lhs.get_equality_type(*rhs)
-}
-
 impl LibrettoEvaluator for LogicEqualityExpr {
     fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
         let mut cardnality = true;
@@ -129,13 +143,17 @@ mod tests {
     fn parse_equality_expr() {
         let ast = parse_expr::<LogicEqualityExpr>("true != false");
         assert_eq!(ast.lhs, parse_expr::<LogicComparisonExpr>("true"));
-        assert_eq!(ast.rhs, vec![(EqualityOperator::NotEqualTo, parse_expr::<LogicComparisonExpr>("false"))]);
+        assert_eq!(ast.rhs, vec![(EqualityOperator::NotEqualTo, parse_expr::<LogicComparisonExpr>("        false"))]);
     }
 
     #[test]
     fn validate_equality_expr() {
         validate_expr::<LogicEqualityExpr>("!false", 0, LsonType::Bool);
         validate_expr::<LogicEqualityExpr>("2 * 2", 0, LsonType::Int);
+        validate_expr::<LogicEqualityExpr>("true != false", 0, LsonType::Bool);
+        validate_expr::<LogicEqualityExpr>("\"a\" == \"a\"", 0, LsonType::Bool);
+        validate_expr::<LogicEqualityExpr>("foo == none", 0, LsonType::Bool);
+        validate_expr::<LogicEqualityExpr>("\"a\" == 1", 1, LsonType::None);
     }
 
     #[test]
@@ -148,4 +166,13 @@ mod tests {
         evaluate_expr::<LogicComparisonExpr>("10 < 15 < 20 > 15 > 10", Lson::Bool(true));
         evaluate_expr::<LogicComparisonExpr>("true != false", Lson::Bool(true));
     }
+
+    #[test]
+    fn eval_equality_expr() {
+        evaluate_expr::<LogicEqualityExpr>("2 == 2.0", Lson::Bool(true));
+        evaluate_expr::<LogicEqualityExpr>("2 != 2.5", Lson::Bool(true));
+        evaluate_expr::<LogicEqualityExpr>("[1, 2] == [1.0, 2]", Lson::Bool(true));
+        evaluate_expr::<LogicEqualityExpr>("{ a : 1, b : 2 } == { b : 2, a : 1 }", Lson::Bool(true));
+        evaluate_expr::<LogicEqualityExpr>("foo == none", Lson::Bool(false));
+    }
 }
\ No newline at end of file
@@ -1,31 +1,48 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::{lson::{Lson, LsonType}, lexer::{LibrettoLogicToken, LogicOrdinal, LibrettoTokenQueue}, parse_ast, compiler::{LibrettoCompiletime, LibrettoCompileError}, runtime::{LibrettoRuntimeResult, LibrettoEvaluator}};
+use crate::{lson::{Lson, LsonType}, lexer::{LibrettoLogicToken, LogicOrdinal, LibrettoTokenQueue}, parse_ast, compiler::{LibrettoCompiletime, LibrettoCompileErrorKind}, runtime::{LibrettoRuntimeResult, LibrettoEvaluator}, span::Span};
 use super::{logic_equality_expr::LogicEqualityExpr, LibrettoParsable};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct LogicExpr {
     expr : LogicEqualityExpr,
-    default : Option<Lson>
+    #[serde(with = "crate::lson::tagged::option")]
+    default : Option<Lson>,
+    span: Span,
+}
+
+impl LogicExpr {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// An expression is constant when it reads no variables, so it always has the same value.
+    pub fn is_constant(&self) -> bool {
+        self.expr.is_constant()
+    }
 }
 
 impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicExpr {
     fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
-        let mut check = LogicEqualityExpr::raw_check(queue);
+        if !LogicEqualityExpr::raw_check(queue) {
+            return false;
+        }
         if queue.next_is(LogicOrdinal::Question) {
-            check &= Lson::raw_check(queue);
+            return Lson::raw_check(queue);
         }
-        check
+        true
     }
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         let expr = parse_ast!(LogicEqualityExpr, queue, compile_time);
         let default = if let Some(_) = queue.pop_if_next_is(LogicOrdinal::Question) {
             Some(parse_ast!(Lson, queue, compile_time))
         } else {
             None
         };
-        Some(LogicExpr{expr, default})
+        Some(LogicExpr{expr, default, span: start.to(queue.last_span())})
     }
 
     fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
@@ -33,7 +50,8 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicExpr {
         if let Some(lson) = &self.default {
             let default_type = lson.validate(compile_time);
             if expected_type != default_type {
-                compile_time.push_error(LibrettoCompileError::ExprDefaultTypeMissmatch(expected_type.to_string(), default_type.to_string()))
+                let error = LibrettoCompileErrorKind::ExprDefaultTypeMissmatch(expected_type.to_string(), default_type.to_string()).at(self.span);
+                compile_time.push(error.with_origin(Some(self.expr.span())));
             }
         };
         expected_type
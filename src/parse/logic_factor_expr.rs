@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::{lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal}, parse_ast, lson::Lson, runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult}, compiler::{LibrettoCompiletime, LibrettoCompileError}};
+use crate::{lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal}, parse_ast, lson::Lson, runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult}, compiler::{LibrettoCompiletime, LibrettoCompileErrorKind}, span::Span};
 use crate::lson::LsonType;
 use super::{logic_unary_expr::LogicUnaryExpr, LibrettoParsable};
 
@@ -8,7 +9,7 @@ use super::{logic_unary_expr::LogicUnaryExpr, LibrettoParsable};
 //          Factor Expression
 //==================================================================================================
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum FactorOperator {
     Mult,
     Div
@@ -23,10 +24,21 @@ impl ToString for FactorOperator {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct LogicFactorExpr {
     lhs : LogicUnaryExpr,
-    rhs : Vec<(FactorOperator, LogicUnaryExpr)>
+    rhs : Vec<(FactorOperator, LogicUnaryExpr)>,
+    span: Span,
+}
+
+impl LogicFactorExpr {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.lhs.is_constant() && self.rhs.iter().all(|(_, rhs)| rhs.is_constant())
+    }
 }
 
 impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicFactorExpr {
@@ -39,6 +51,7 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicFactorExpr {
     }
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         let lhs = parse_ast!(LogicUnaryExpr, queue, compile_time);
         let mut rhs = Vec::new();
 
@@ -62,35 +75,25 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicFactorExpr {
             }
         }
 
-        Some(LogicFactorExpr { lhs, rhs })
+        Some(LogicFactorExpr { lhs, rhs, span: start.to(queue.last_span()) })
     }
 
     fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
-        let lhs = self.lhs.validate(compile_time);
-
-        if let Some((op, rhs)) = self.rhs.first() {
-            let rhs = rhs.validate(compile_time);
-            let mut expected_type = get_factor_type(&lhs, op, &rhs);
+        let mut lhs_type = self.lhs.validate(compile_time);
 
-            if expected_type == LsonType::None {
-                compile_time.push_error(LibrettoCompileError::InvalidOperationError(lhs.to_string(), op.to_string(), rhs.to_string()));
+        // An operation that doesn't fit is reported from the start of the expression to its right
+        // side, since that is what the left side's type came from.
+        for (op, rhs) in &self.rhs {
+            let rhs_type = rhs.validate(compile_time);
+            let op_type = get_factor_type(&lhs_type, op, &rhs_type);
+            if op_type == LsonType::None {
+                let span = self.lhs.span().to(rhs.span());
+                compile_time.push_error(LibrettoCompileErrorKind::InvalidOperationError(lhs_type.to_string(), op.to_string(), rhs_type.to_string()), span);
                 return LsonType::None;
             }
-
-            for i in 1..self.rhs.len() {
-                let (inner_op, inner)= &self.rhs[i];
-                let inner_type = inner.validate(compile_time);
-                let op_type = get_factor_type(&expected_type, inner_op, &inner_type);
-                if op_type == LsonType::None{
-                    compile_time.push_error(LibrettoCompileError::InvalidOperationError(expected_type.to_string(), op.to_string(), inner_type.to_string()));
-                    return LsonType::None;
-                }
-            }
-
-            expected_type
-        } else {
-            lhs
+            lhs_type = op_type;
         }
+        lhs_type
     }
 }
 
@@ -142,7 +145,7 @@ mod tests {
     fn parse_factor_expr() {
         let ast = parse_expr::<LogicFactorExpr>("2*4*6");
         assert_eq!(ast.lhs, parse_expr::<LogicUnaryExpr>("2"));
-        assert_eq!(ast.rhs, vec![(FactorOperator::Mult, parse_expr::<LogicUnaryExpr>("4")), (FactorOperator::Mult, parse_expr::<LogicUnaryExpr>("6"))]);
+        assert_eq!(ast.rhs, vec![(FactorOperator::Mult, parse_expr::<LogicUnaryExpr>("  4")), (FactorOperator::Mult, parse_expr::<LogicUnaryExpr>("    6"))]);
     }
 
     #[test]
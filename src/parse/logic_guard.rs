@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::{LibrettoCompileErrorKind, LibrettoCompileWarningKind, LibrettoCompiletime},
+    lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal},
+    lson::{Lson, LsonType},
+    parse_ast,
+    runtime::{LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeError, LibrettoRuntimeErrorKind, LibrettoRuntimeResult},
+    span::Span,
+};
+
+use super::{logic_expr::LogicExpr, LibrettoParsable};
+
+//==================================================================================================
+//          Logic Guard
+//==================================================================================================
+
+/// A guard on a response, written as `<if cond>` or `<if cond else "reason">`. When the condition
+/// fails, a response without a reason is hidden, and a response with a reason is shown as disabled.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogicGuard {
+    condition: LogicExpr,
+    reason: Option<LogicExpr>,
+    span: Span,
+}
+
+impl LogicGuard {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn condition(&self) -> &LogicExpr {
+        &self.condition
+    }
+
+    pub fn reason(&self) -> Option<&LogicExpr> {
+        self.reason.as_ref()
+    }
+
+    /// Evaluates the guard. Returns `None` if the condition passed, or the reason it failed. A failed
+    /// guard without a reason gives `Lson::None` as its reason.
+    pub fn check_guard(&self, runtime: &mut LibrettoRuntime) -> Result<Option<Lson>, LibrettoRuntimeError> {
+        let passed = self.evaluate(runtime)?;
+        if passed == Lson::Bool(true) {
+            return Ok(None);
+        }
+
+        match &self.reason {
+            Some(reason) => Ok(Some(reason.evaluate(runtime)?)),
+            None => Ok(Some(Lson::None)),
+        }
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicGuard {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
+        if !(queue.next_is(LogicOrdinal::If) && LogicExpr::raw_check(queue)) {
+            return false;
+        }
+        if queue.next_is(LogicOrdinal::Else) && !LogicExpr::raw_check(queue) {
+            return false;
+        }
+        queue.is_exhausted()
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        queue.pop_if_next_is(LogicOrdinal::If)?;
+        let condition = parse_ast!(LogicExpr, queue, compile_time);
+        let reason = if queue.pop_if_next_is(LogicOrdinal::Else).is_some() {
+            Some(parse_ast!(LogicExpr, queue, compile_time))
+        } else {
+            None
+        };
+        Some(LogicGuard { condition, reason, span: start.to(queue.last_span()) })
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        let condition_type = self.condition.validate(compile_time);
+        if condition_type != LsonType::Bool {
+            compile_time.push_error(LibrettoCompileErrorKind::ConditionNotBool(condition_type.to_string()), self.condition.span());
+        } else if self.condition.is_constant() {
+            compile_time.push_warning(LibrettoCompileWarningKind::ConstantCondition, self.condition.span());
+        }
+
+        if let Some(reason) = &self.reason {
+            let reason_type = reason.validate(compile_time);
+            if reason_type != LsonType::String {
+                compile_time.push_error(LibrettoCompileErrorKind::GuardReasonNotString(reason_type.to_string()), reason.span());
+            }
+        }
+        LsonType::Bool
+    }
+}
+
+impl LibrettoEvaluator for LogicGuard {
+    fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
+        let value = self.condition.evaluate(runtime)?;
+        if value.is_bool() {
+            Ok(value)
+        } else {
+            Err(LibrettoRuntimeErrorKind::ConditionNotBool(value.get_type().to_string()).at(self.condition.span()))
+        }
+    }
+}
+
+//==================================================================================================
+//          Logic Guard Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lson::{Lson, LsonType},
+        parse::test_util::*,
+    };
+
+    use super::*;
+
+    #[test]
+    fn check_logic_guard() {
+        check_expr::<LogicGuard>("if bar", 2);
+        check_expr::<LogicGuard>("if foo > 2.0 else \"Not enough.\"", 6);
+    }
+
+    #[test]
+    fn parse_logic_guard() {
+        let ast = parse_expr::<LogicGuard>("if bar");
+        assert_eq!(ast.condition().span(), Span::new(3, 6, 1, 4));
+        assert!(ast.reason().is_none());
+
+        let ast = parse_expr::<LogicGuard>("if bar else \"Not enough.\"");
+        assert_eq!(ast.reason().map(|reason| reason.span()), Some(Span::new(12, 25, 1, 13)));
+        assert_eq!(ast.span(), Span::new(0, 25, 1, 1));
+    }
+
+    #[test]
+    fn validate_logic_guard() {
+        validate_expr::<LogicGuard>("if bar", 0, LsonType::Bool);
+        validate_expr::<LogicGuard>("if foo", 1, LsonType::Bool);
+        validate_expr::<LogicGuard>("if bar else 2", 1, LsonType::Bool);
+        validate_expr::<LogicGuard>("if foo else 2", 2, LsonType::Bool);
+    }
+
+    #[test]
+    fn evaluate_logic_guard() {
+        evaluate_expr::<LogicGuard>("if bar", Lson::Bool(true));
+        evaluate_expr::<LogicGuard>("if foo > 3.0 else \"Too small.\"", Lson::Bool(false));
+    }
+}
@@ -1,17 +1,29 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::{lexer::{LibrettoTokenQueue, LibrettoLogicToken, LogicOrdinal}, lson::{LsonType, Lson}, compiler::{LibrettoCompiletime, LibrettoCompileError}, runtime::{LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeResult}};
+use crate::{lexer::{LibrettoTokenQueue, LibrettoLogicToken, LogicOrdinal}, lson::{LsonType, Lson}, compiler::{LibrettoCompiletime, LibrettoCompileErrorKind}, runtime::{LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeResult}, span::Span};
 use crate::parse::util::TypedIdentifier;
 use super::{logic_equality_expr::LogicEqualityExpr, LibrettoParsable, logic_assignment_stmt::LogicAssignmentStatement, util::KeyValuePair, logic_expr::LogicExpr};
 
+#[derive(Serialize, Deserialize)]
 pub struct LogicLetStatement {
     identifier: TypedIdentifier,
-    value : Option<LogicExpr>
+    value : Option<LogicExpr>,
+    span: Span,
+}
+
+impl LogicLetStatement {
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
 impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicLetStatement {
     fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
-        let first = queue.next_is(LogicOrdinal::Let) && TypedIdentifier::raw_check(queue);
+        if !(queue.next_is(LogicOrdinal::Let) && TypedIdentifier::raw_check(queue)) {
+            return false;
+        }
+
         if queue.next_is(LogicOrdinal::Semicolon) {
             return true;
         }
@@ -24,15 +36,16 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicLetStatement {
     }
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         queue.pop();
         if let Some(identifier) = TypedIdentifier::parse(queue, compile_time) {
             let next = queue.pop();
             if let Some(LibrettoLogicToken::Semicolon) = &next {
-                return Some(LogicLetStatement { identifier, value: None })
+                return Some(LogicLetStatement { identifier, value: None, span: start.to(queue.last_span()) })
             };
             if let Some(LibrettoLogicToken::Equals) = &next {
                 let value = LogicExpr::parse(queue, compile_time);
-                return Some(LogicLetStatement { identifier, value })
+                return Some(LogicLetStatement { identifier, value, span: start.to(queue.last_span()) })
             }
         }
 
@@ -48,14 +61,16 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicLetStatement {
             LsonType::None
         };
 
+        // Without a type or a value, the variable is declared as pending and takes the type of the
+        // first assignment to it.
         match (declared_type, rhs_type) {
-            (LsonType::None, LsonType::None) => compile_time.push_error(LibrettoCompileError::TypeNotExplicit(ident.to_string())),
-            (_, LsonType::None) => compile_time.insert_variable_type(ident, declared_type),
-            (LsonType::None, _) => compile_time.insert_variable_type(ident, rhs_type),
+            (_, LsonType::None) => compile_time.declare_variable(ident, declared_type, self.identifier.span(), false),
+            (LsonType::None, _) => compile_time.declare_variable(ident, rhs_type, self.identifier.span(), false),
             _ => {
-                if declared_type != rhs_type {
-                    compile_time.push_error(LibrettoCompileError::AssignmentStatementTypeMismatch(declared_type.to_string(), rhs_type.to_string()));
+                if !declared_type.accepts(rhs_type) {
+                    compile_time.push_error(LibrettoCompileErrorKind::AssignmentStatementTypeMismatch(declared_type.to_string(), rhs_type.to_string()), self.span);
                 }
+                compile_time.declare_variable(ident, declared_type, self.identifier.span(), false);
             }
         }
 
@@ -65,11 +80,14 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicLetStatement {
 
 impl LibrettoEvaluator for LogicLetStatement {
     fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
-        if let Some(lhs) = &self.value {
-            let ident = self.identifier.ident();
-            let value = lhs.evaluate(runtime)?;
-            runtime.insert_data(ident, value);
-        }
+        let ident = self.identifier.ident();
+        let declared_type = self.identifier.implicit_type().unwrap_or(LsonType::None);
+        let value = if let Some(lhs) = &self.value {
+            lhs.evaluate(runtime)?.coerce_to(declared_type)
+        } else {
+            declared_type.default_value()
+        };
+        runtime.insert_data(ident, value);
 
         Ok(Lson::None)
     }
@@ -97,12 +115,14 @@ mod tests {
     fn parse_let_stmt() {
         let ast = parse_expr::<LogicLetStatement>("let test : bool;");
         assert_eq!(ast.value, None);
-        assert_eq!(ast.identifier, parse_expr::<TypedIdentifier>("test : bool"))
+        assert_eq!(ast.identifier, parse_expr::<TypedIdentifier>("    test : bool"))
     }
 
     #[test]
     fn validate_let_stmt() {
-        validate_expr::<LogicLetStatement>("let test;", 1, LsonType::None);
+        validate_expr::<LogicLetStatement>("let test;", 0, LsonType::None);
+        validate_expr::<LogicLetStatement>("let test : float = 2;", 0, LsonType::None);
+        validate_expr::<LogicLetStatement>("let test : int = 2.0;", 1, LsonType::None);
         validate_expr::<LogicLetStatement>("let test : bool = 2.0;", 1, LsonType::None);
         validate_expr::<LogicLetStatement>("let test : bool;", 0, LsonType::None);
         validate_expr::<LogicLetStatement>("let test = false;", 0, LsonType::None);
@@ -113,5 +133,14 @@ mod tests {
         let rt = evaluate_expr::<LogicLetStatement>("let test : float = 2.0;", Lson::None);
         assert!(rt.has_data("test"));
         assert_eq!(rt.get_data("test"), Lson::Float(2.0));
+
+        let rt = evaluate_expr::<LogicLetStatement>("let test : float = 2;", Lson::None);
+        assert_eq!(rt.get_data("test"), Lson::Float(2.0));
+
+        let rt = evaluate_expr::<LogicLetStatement>("let test : float;", Lson::None);
+        assert!(matches!(rt.get_data("test"), Lson::Float(value) if value == 0.0));
+
+        let rt = evaluate_expr::<LogicLetStatement>("let test;", Lson::None);
+        assert!(matches!(rt.get_data("test"), Lson::None));
     }
 }
\ No newline at end of file
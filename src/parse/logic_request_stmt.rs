@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::{LibrettoCompileErrorKind, LibrettoCompiletime},
+    lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal},
+    lson::{Lson, LsonType},
+    parse_ast,
+    runtime::{LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeErrorKind, LibrettoRuntimeResult},
+    span::Span,
+};
+
+use super::{util::TypedIdentifier, LibrettoParsable};
+
+//==================================================================================================
+//          Logic Request Statement
+//==================================================================================================
+
+/// Asks the host for a value, like `request status : string ? "moderate";`, and declares it as a
+/// variable. Without a default, the request fails if the host doesn't have the value. The type can
+/// be left out when there is a default to take it from.
+#[derive(Serialize, Deserialize)]
+pub struct LogicRequestStatement {
+    identifier: TypedIdentifier,
+    #[serde(with = "crate::lson::tagged::option")]
+    default: Option<Lson>,
+    span: Span,
+}
+
+impl LogicRequestStatement {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn ident(&self) -> &str {
+        self.identifier.ident()
+    }
+
+    /// The type the request was declared with, or the type of its default.
+    fn declared_type(&self) -> LsonType {
+        match (self.identifier.implicit_type(), &self.default) {
+            (Some(declared), _) => declared,
+            (None, Some(default)) => default.get_type(),
+            (None, None) => LsonType::None,
+        }
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicRequestStatement {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
+        if !(queue.next_is(LogicOrdinal::Request) && TypedIdentifier::raw_check(queue)) {
+            return false;
+        }
+        if queue.next_is(LogicOrdinal::Question) {
+            return Lson::raw_check(queue);
+        }
+        true
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
+        queue.pop_if_next_is(LogicOrdinal::Request)?;
+        let identifier = parse_ast!(TypedIdentifier, queue, compile_time);
+        let default = if queue.pop_if_next_is(LogicOrdinal::Question).is_some() {
+            Some(parse_ast!(Lson, queue, compile_time))
+        } else {
+            None
+        };
+        Some(LogicRequestStatement { identifier, default, span: start.to(queue.last_span()) })
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        let ident = self.ident();
+        let declared_type = self.declared_type();
+        if declared_type == LsonType::None {
+            compile_time.push_error(LibrettoCompileErrorKind::TypeNotExplicit(ident.to_string()), self.span);
+            return LsonType::None;
+        }
+        if let Some(default) = &self.default {
+            let default_type = default.validate(compile_time);
+            if default_type != declared_type {
+                compile_time.push_error(LibrettoCompileErrorKind::ExprDefaultTypeMissmatch(declared_type.to_string(), default_type.to_string()), self.span);
+            }
+        }
+        compile_time.declare_variable(ident, declared_type, self.identifier.span(), true);
+        LsonType::None
+    }
+}
+
+impl LibrettoEvaluator for LogicRequestStatement {
+    fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
+        let ident = self.ident();
+        let value = match runtime.request(ident).or_else(|| self.default.clone()) {
+            Some(value) => value,
+            None => return Err(LibrettoRuntimeErrorKind::RequestNotAvailable(ident.to_string()).at(self.span)),
+        };
+
+        let declared_type = self.declared_type();
+        if value.get_type() != declared_type {
+            return Err(LibrettoRuntimeErrorKind::RequestTypeMismatch(ident.to_string(), declared_type.to_string(), value.get_type().to_string()).at(self.span));
+        }
+
+        runtime.insert_data(ident, value);
+        Ok(Lson::None)
+    }
+}
+
+//==================================================================================================
+//          Logic Request Statement Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use logos::Logos;
+
+    use crate::{
+        parse::test_util::*,
+        runtime::LibrettoRuntimeError,
+    };
+
+    use super::*;
+
+    #[test]
+    fn check_request_stmt() {
+        check_expr::<LogicRequestStatement>("request standing : int", 4);
+        check_expr::<LogicRequestStatement>("request status : string ? \"moderate\"", 6);
+        check_expr::<LogicRequestStatement>("request player ? false", 4);
+    }
+
+    #[test]
+    fn parse_request_stmt() {
+        let ast = parse_expr::<LogicRequestStatement>("request player ? false");
+        assert_eq!(ast.ident(), "player");
+        assert_eq!(ast.default, Some(Lson::Bool(false)));
+        assert_eq!(ast.declared_type(), LsonType::Bool);
+    }
+
+    #[test]
+    fn validate_request_stmt() {
+        validate_expr::<LogicRequestStatement>("request standing : int", 0, LsonType::None);
+        validate_expr::<LogicRequestStatement>("request standing", 1, LsonType::None);
+        validate_expr::<LogicRequestStatement>("request status : string ? 2", 1, LsonType::None);
+    }
+
+    #[test]
+    fn evaluate_request_stmt() {
+        let runtime = evaluate_expr::<LogicRequestStatement>("request player ? false", Lson::None);
+        assert_eq!(runtime.get_data("player"), Lson::Bool(false));
+
+        let mut runtime = LibrettoRuntime::default();
+        runtime.set_request_handler(|ident| (ident == "standing").then_some(Lson::Int(20)));
+        let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer("request standing : int"));
+        let ast = LogicRequestStatement::checked_parse(&mut queue, &mut LibrettoCompiletime::default()).unwrap();
+        ast.evaluate(&mut runtime).unwrap();
+        assert_eq!(runtime.get_data("standing"), Lson::Int(20));
+
+        let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer("request roster : array"));
+        let ast = LogicRequestStatement::checked_parse(&mut queue, &mut LibrettoCompiletime::default()).unwrap();
+        assert!(matches!(ast.evaluate(&mut runtime), Err(LibrettoRuntimeError { kind: LibrettoRuntimeErrorKind::RequestNotAvailable(_), .. })));
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::LibrettoCompiletime,
+    lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal},
+    lson::LsonType,
+    parse_ast,
+    runtime::{LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeResult},
+    span::Span,
+};
+
+use super::{
+    logic_assignment_stmt::LogicAssignmentStatement, logic_expr::LogicExpr,
+    logic_let_stmt::LogicLetStatement, logic_request_stmt::LogicRequestStatement,
+    logic_yield_stmt::LogicYieldStatement, LibrettoParsable,
+};
+
+//==================================================================================================
+//          Logic Statement
+//==================================================================================================
+
+#[derive(Serialize, Deserialize)]
+pub enum LogicStatement {
+    Let(LogicLetStatement),
+    Assignment(LogicAssignmentStatement),
+    Yield(LogicYieldStatement),
+    Request(LogicRequestStatement),
+    Expr(LogicExpr),
+}
+
+impl LogicStatement {
+    pub fn span(&self) -> Span {
+        match self {
+            LogicStatement::Let(stmt) => stmt.span(),
+            LogicStatement::Assignment(stmt) => stmt.span(),
+            LogicStatement::Yield(stmt) => stmt.span(),
+            LogicStatement::Request(stmt) => stmt.span(),
+            LogicStatement::Expr(expr) => expr.span(),
+        }
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicStatement {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
+        let start = queue.cursor();
+        let check = LogicLetStatement::raw_check(queue) || {
+            queue.rewind_to(start);
+            LogicAssignmentStatement::raw_check(queue)
+        } || {
+            queue.rewind_to(start);
+            LogicYieldStatement::raw_check(queue)
+        } || {
+            queue.rewind_to(start);
+            LogicRequestStatement::raw_check(queue)
+        } || {
+            queue.rewind_to(start);
+            LogicExpr::raw_check(queue)
+        };
+
+        if check {
+            queue.next_is(LogicOrdinal::Semicolon);
+        }
+        check
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        queue.reset();
+        let statement = if LogicLetStatement::check(queue) {
+            LogicStatement::Let(parse_ast!(LogicLetStatement, queue, compile_time))
+        } else if LogicAssignmentStatement::check(queue) {
+            LogicStatement::Assignment(parse_ast!(LogicAssignmentStatement, queue, compile_time))
+        } else if LogicYieldStatement::check(queue) {
+            LogicStatement::Yield(parse_ast!(LogicYieldStatement, queue, compile_time))
+        } else if LogicRequestStatement::check(queue) {
+            LogicStatement::Request(parse_ast!(LogicRequestStatement, queue, compile_time))
+        } else {
+            LogicStatement::Expr(parse_ast!(LogicExpr, queue, compile_time))
+        };
+        queue.pop_if_next_is(LogicOrdinal::Semicolon);
+        Some(statement)
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        match self {
+            LogicStatement::Let(stmt) => stmt.validate(compile_time),
+            LogicStatement::Assignment(stmt) => stmt.validate(compile_time),
+            LogicStatement::Yield(stmt) => stmt.validate(compile_time),
+            LogicStatement::Request(stmt) => stmt.validate(compile_time),
+            LogicStatement::Expr(expr) => expr.validate(compile_time),
+        }
+    }
+}
+
+impl LibrettoEvaluator for LogicStatement {
+    fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
+        match self {
+            LogicStatement::Let(stmt) => stmt.evaluate(runtime),
+            LogicStatement::Assignment(stmt) => stmt.evaluate(runtime),
+            LogicStatement::Yield(stmt) => stmt.evaluate(runtime),
+            LogicStatement::Request(stmt) => stmt.evaluate(runtime),
+            LogicStatement::Expr(expr) => expr.evaluate(runtime),
+        }
+    }
+}
+
+//==================================================================================================
+//          Logic Statement Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lson::{Lson, LsonType},
+        parse::test_util::*,
+    };
+
+    use super::*;
+
+    #[test]
+    fn check_logic_stmt() {
+        check_expr::<LogicStatement>("let test = 2;", 5);
+        check_expr::<LogicStatement>("foo = 2.0;", 4);
+        check_expr::<LogicStatement>("foo = 2.0", 3);
+        check_expr::<LogicStatement>("2 + 2", 3);
+        check_expr::<LogicStatement>("yield;", 2);
+    }
+
+    #[test]
+    fn parse_logic_stmt() {
+        assert!(matches!(parse_expr::<LogicStatement>("let test = 2;"), LogicStatement::Let(_)));
+        assert!(matches!(parse_expr::<LogicStatement>("foo = 2.0;"), LogicStatement::Assignment(_)));
+        assert!(matches!(parse_expr::<LogicStatement>("foo"), LogicStatement::Expr(_)));
+        assert!(matches!(parse_expr::<LogicStatement>("wait 1.0;"), LogicStatement::Yield(_)));
+    }
+
+    #[test]
+    fn validate_logic_stmt() {
+        validate_expr::<LogicStatement>("let test = 2;", 0, LsonType::None);
+        validate_expr::<LogicStatement>("foo = true;", 1, LsonType::Float);
+        validate_expr::<LogicStatement>("bar", 0, LsonType::Bool);
+    }
+
+    #[test]
+    fn evaluate_logic_stmt() {
+        let rt = evaluate_expr::<LogicStatement>("foo = 3.0;", Lson::Float(3.0));
+        assert_eq!(rt.get_data("foo"), Lson::Float(3.0));
+    }
+}
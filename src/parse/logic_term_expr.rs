@@ -1,10 +1,12 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::compiler::{LibrettoCompiletime, LibrettoCompileError};
+use crate::compiler::{LibrettoCompiletime, LibrettoCompileErrorKind};
 use crate::lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal};
 use crate::lson::{LsonType, Lson};
 use crate::parse_ast;
 use crate::runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult};
+use crate::span::Span;
 use super::logic_factor_expr::LogicFactorExpr;
 use super::LibrettoParsable;
 
@@ -12,7 +14,7 @@ use super::LibrettoParsable;
 //          Additive Expression
 //==================================================================================================
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum TermOperator {
     Plus,
     Minus
@@ -27,10 +29,21 @@ impl ToString for TermOperator {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct LogicTermExpr {
     lhs: LogicFactorExpr,
     rhs: Vec<(TermOperator, LogicFactorExpr)>,
+    span: Span,
+}
+
+impl LogicTermExpr {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.lhs.is_constant() && self.rhs.iter().all(|(_, rhs)| rhs.is_constant())
+    }
 }
 
 impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicTermExpr {
@@ -44,6 +57,7 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicTermExpr {
     }
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         let lhs = parse_ast!(LogicFactorExpr, queue, compile_time);
         let mut rhs = Vec::new();
 
@@ -67,35 +81,25 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicTermExpr {
             }
         }
 
-        Some(LogicTermExpr { lhs, rhs })
+        Some(LogicTermExpr { lhs, rhs, span: start.to(queue.last_span()) })
     }
 
     fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
-        let lhs = self.lhs.validate(compile_time);
-
-        if let Some((op, rhs)) = self.rhs.first() {
-            let rhs = rhs.validate(compile_time);
-            let mut expected_type = get_term_type(&lhs, op, &rhs);
+        let mut lhs_type = self.lhs.validate(compile_time);
 
-            if expected_type == LsonType::None {
-                compile_time.push_error(LibrettoCompileError::InvalidOperationError(lhs.to_string(), op.to_string(), rhs.to_string()));
+        // An operation that doesn't fit is reported from the start of the expression to its right
+        // side, since that is what the left side's type came from.
+        for (op, rhs) in &self.rhs {
+            let rhs_type = rhs.validate(compile_time);
+            let op_type = get_term_type(&lhs_type, op, &rhs_type);
+            if op_type == LsonType::None {
+                let span = self.lhs.span().to(rhs.span());
+                compile_time.push_error(LibrettoCompileErrorKind::InvalidOperationError(lhs_type.to_string(), op.to_string(), rhs_type.to_string()), span);
                 return LsonType::None;
             }
-
-            for i in 1..self.rhs.len() {
-                let (inner_op, inner)= &self.rhs[i];
-                let inner_type = inner.validate(compile_time);
-                let op_type = get_term_type(&expected_type, inner_op, &inner_type);
-                if op_type == LsonType::None{
-                    compile_time.push_error(LibrettoCompileError::InvalidOperationError(expected_type.to_string(), op.to_string(), inner_type.to_string()));
-                    return LsonType::None;
-                }
-            }
-
-            expected_type
-        } else {
-            lhs
+            lhs_type = op_type;
         }
+        lhs_type
     }
 }
 
@@ -127,9 +131,14 @@ impl LibrettoEvaluator for LogicTermExpr {
 #[cfg(test)]
 mod tests {
 
+    use logos::Logos;
+
     use crate::{
+        compiler::{LibrettoCompiletime, LibrettoCompileErrorKind},
+        lexer::{LibrettoLogicToken, LibrettoTokenQueue},
         lson::{Lson, LsonType},
-        parse::{test_util::*, logic_value::LogicValue, logic_factor_expr::LogicFactorExpr},
+        parse::{test_util::*, logic_value::LogicValue, logic_factor_expr::LogicFactorExpr, LibrettoParsable},
+        span::Span,
     };
 
     use super::{LogicTermExpr, TermOperator};
@@ -146,12 +155,12 @@ mod tests {
     fn parse_term_expr() {
         let ast = parse_expr::<LogicTermExpr>("2+3");
         assert_eq!(ast.lhs, parse_expr::<LogicFactorExpr>("2"));
-        assert_eq!(ast.rhs[0], (TermOperator::Plus, parse_expr::<LogicFactorExpr>("3")));
+        assert_eq!(ast.rhs[0], (TermOperator::Plus, parse_expr::<LogicFactorExpr>("  3")));
 
         let ast = parse_expr::<LogicTermExpr>("2+2+2");
         assert_eq!(ast.lhs, parse_expr::<LogicFactorExpr>("2"));
-        assert_eq!(ast.rhs[0], (TermOperator::Plus, parse_expr::<LogicFactorExpr>("2")));
-        assert_eq!(ast.rhs[1], (TermOperator::Plus, parse_expr::<LogicFactorExpr>("2")));
+        assert_eq!(ast.rhs[0], (TermOperator::Plus, parse_expr::<LogicFactorExpr>("  2")));
+        assert_eq!(ast.rhs[1], (TermOperator::Plus, parse_expr::<LogicFactorExpr>("    2")));
     }
 
     #[test]
@@ -160,6 +169,14 @@ mod tests {
         validate_expr::<LogicTermExpr>("2 + 2 * 3", 0, LsonType::Int);
         validate_expr::<LogicTermExpr>("2 + foo", 0, LsonType::Float);
         validate_expr::<LogicTermExpr>("false + 3", 1, LsonType::None);
+        validate_expr::<LogicTermExpr>("2 + 2.5 - 1", 0, LsonType::Float);
+
+        let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer("2 + 2.5 - true + 1"));
+        let mut compile_time = LibrettoCompiletime::default();
+        LogicTermExpr::checked_parse(&mut queue, &mut compile_time).unwrap().validate(&mut compile_time);
+        let error = &compile_time.errors()[0];
+        assert_eq!(error.kind, LibrettoCompileErrorKind::InvalidOperationError("float".to_string(), "-".to_string(), "bool".to_string()));
+        assert_eq!(error.span, Span::new(0, 14, 1, 1));
     }
 
     #[test]
@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::{lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal}, parse_ast, lson::Lson, runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult}, compiler::{LibrettoCompiletime, LibrettoCompileError}};
+use crate::{lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal}, parse_ast, lson::Lson, runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult}, compiler::{LibrettoCompiletime, LibrettoCompileErrorKind}, span::Span};
 use crate::lson::LsonType;
 use super::{logic_value::LogicValue, LibrettoParsable};
 
@@ -8,16 +9,39 @@ use super::{logic_value::LogicValue, LibrettoParsable};
 //          Logic Unary Expression
 //==================================================================================================
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UnaryOperator {
     Negative,
     Bang,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct LogicUnaryExpr {
     operator: Option<UnaryOperator>,
     value: LogicValue,
+    span: Span,
+}
+
+impl LogicUnaryExpr {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.value.is_constant()
+    }
+
+    /// A `-` right before a number is read as part of the literal rather than as an operator, so
+    /// `-5` is the constant `-5` and not `5` negated when the script runs. This looks from the next
+    /// token to be popped, and leaves the cursor where it was.
+    fn next_is_negative_literal(queue: &mut LibrettoTokenQueue<'_, LibrettoLogicToken>) -> bool {
+        let cursor = queue.cursor();
+        queue.rewind();
+        let is_literal = queue.next_is(LogicOrdinal::Sub)
+            && queue.next_is([LogicOrdinal::IntLiteral, LogicOrdinal::FloatLiteral]);
+        queue.rewind_to(cursor);
+        is_literal
+    }
 }
 
 impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicUnaryExpr {
@@ -31,7 +55,12 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicUnaryExpr {
     }
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
-        let operator = queue.pop_if_next_is([LogicOrdinal::Bang, LogicOrdinal::Sub]);
+        let start = queue.next_span();
+        let operator = if Self::next_is_negative_literal(queue) {
+            None
+        } else {
+            queue.pop_if_next_is([LogicOrdinal::Bang, LogicOrdinal::Sub])
+        };
         let operator = if let Some(token) = operator {
             match token {
                 LibrettoLogicToken::Sub => Some(UnaryOperator::Negative),
@@ -45,6 +74,7 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicUnaryExpr {
 
         Some(LogicUnaryExpr {
             operator,
+            span: start.to(value.span()),
             value,
         })
     }
@@ -56,24 +86,24 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicUnaryExpr {
             match op {
                 UnaryOperator::Negative => {
                     match lson_type {
-                        LsonType::None => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("-".to_string(), "null".to_string())),
-                        LsonType::String => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("-".to_string(), "string".to_string())),
-                        LsonType::Bool => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("-".to_string(), "bool".to_string())),
-                        LsonType::Array => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("-".to_string(), "array".to_string())),
-                        LsonType::Struct => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("-".to_string(), "struct".to_string())),
-                        LsonType::Function => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("-".to_string(), "function".to_string())),
+                        LsonType::None => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("-".to_string(), "null".to_string()), self.span),
+                        LsonType::String => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("-".to_string(), "string".to_string()), self.span),
+                        LsonType::Bool => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("-".to_string(), "bool".to_string()), self.span),
+                        LsonType::Array => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("-".to_string(), "array".to_string()), self.span),
+                        LsonType::Struct => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("-".to_string(), "struct".to_string()), self.span),
+                        LsonType::Function => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("-".to_string(), "function".to_string()), self.span),
                         _ => {}
                     }
                 },
                 UnaryOperator::Bang => {
                     match lson_type {
-                        LsonType::None => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("!".to_string(), "null".to_string())),
-                        LsonType::Int => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("!".to_string(), "int".to_string())),
-                        LsonType::Float => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("!".to_string(), "float".to_string())),
-                        LsonType::String => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("!".to_string(), "string".to_string())),
-                        LsonType::Array => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("!".to_string(), "array".to_string())),
-                        LsonType::Struct => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("!".to_string(), "struct".to_string())),
-                        LsonType::Function => compile_time.push_error(LibrettoCompileError::OperationNotSupportedError("!".to_string(), "function".to_string())),
+                        LsonType::None => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("!".to_string(), "null".to_string()), self.span),
+                        LsonType::Int => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("!".to_string(), "int".to_string()), self.span),
+                        LsonType::Float => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("!".to_string(), "float".to_string()), self.span),
+                        LsonType::String => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("!".to_string(), "string".to_string()), self.span),
+                        LsonType::Array => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("!".to_string(), "array".to_string()), self.span),
+                        LsonType::Struct => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("!".to_string(), "struct".to_string()), self.span),
+                        LsonType::Function => compile_time.push_error(LibrettoCompileErrorKind::OperationNotSupportedError("!".to_string(), "function".to_string()), self.span),
                         _ => {}
                     }
                 },
@@ -124,15 +154,22 @@ mod tests {
     fn parse_unary_expr() {
         let ast = parse_expr::<LogicUnaryExpr>("!false");
         assert_eq!(ast.operator, Some(UnaryOperator::Bang));
-        assert_eq!(ast.value, LogicValue::Literal(Lson::Bool(false)));
+        assert!(matches!(&ast.value, LogicValue::Literal(value, _) if *value == Lson::Bool(false)));
 
         let ast = parse_expr::<LogicUnaryExpr>("-12");
+        assert_eq!(ast.operator, None);
+        assert!(matches!(&ast.value, LogicValue::Literal(value, _) if *value == Lson::Int(-12)));
+
+        let ast = parse_expr::<LogicUnaryExpr>("-foo");
         assert_eq!(ast.operator, Some(UnaryOperator::Negative));
-        assert_eq!(ast.value, LogicValue::Literal(Lson::Int(12)));
+        assert!(matches!(&ast.value, LogicValue::Variable(name, _) if name == "foo"));
+
+        let ast = parse_expr::<LogicUnaryExpr>("-9223372036854775808");
+        assert!(matches!(&ast.value, LogicValue::Literal(value, _) if *value == Lson::Int(i64::MIN)));
 
         let ast = parse_expr::<LogicUnaryExpr>("3.14");
         assert_eq!(ast.operator, None);
-        assert_eq!(ast.value, LogicValue::Literal(Lson::Float(3.14)));
+        assert!(matches!(&ast.value, LogicValue::Literal(value, _) if *value == Lson::Float(3.14)));
     }
 
     #[test]
@@ -140,6 +177,7 @@ mod tests {
         validate_expr::<LogicUnaryExpr>("!false", 0, LsonType::Bool);
         validate_expr::<LogicUnaryExpr>("-1", 0, LsonType::Int);
         validate_expr::<LogicUnaryExpr>("-false", 1, LsonType::Bool);
+        validate_expr::<LogicUnaryExpr>("-2.5", 0, LsonType::Float);
     }
 
     #[test]
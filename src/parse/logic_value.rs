@@ -1,41 +1,75 @@
-use std::collections::HashMap;
+use std::str::FromStr;
+
+use logos::Logos;
+use serde::{Deserialize, Serialize};
 
 use super::{
     util::{CommaSeparatedList, KeyValuePair}, LibrettoParsable, logic_equality_expr::LogicEqualityExpr, logic_expr::LogicExpr,
 };
 use crate::{
     lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal, Ordinal},
-    lson::{Lson, LsonType},
-    parse_ast, runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult}, compiler::LibrettoCompiletime,
+    lson::{Lson, LsonError, LsonMap, LsonType},
+    parse_ast, runtime::{LibrettoRuntime, LibrettoEvaluator, LibrettoRuntimeResult}, compiler::{LibrettoCompileErrorKind, LibrettoCompiletime},
+    span::Span,
 };
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum LogicValue {
-    Literal(Lson),
-    Variable(String),
+    Literal(#[serde(with = "crate::lson::tagged")] Lson, Span),
+    Variable(String, Span),
+    /// A field read from a variable, like `player.name`, kept as the variable and the dotted path
+    /// after it.
+    Member(String, String, Span),
 }
-impl From<Lson> for LogicValue {
-    fn from(value: Lson) -> Self {
-        LogicValue::Literal(value)
+
+impl LogicValue {
+    pub fn span(&self) -> Span {
+        match self {
+            LogicValue::Literal(_, span) | LogicValue::Variable(_, span) | LogicValue::Member(_, _, span) => *span,
+        }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        matches!(self, LogicValue::Literal(..))
     }
 }
 
 impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicValue {
 
     fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
-        queue.next_is(LogicOrdinal::Identifier) || Lson::raw_check(queue)
+        if queue.next_is(LogicOrdinal::Identifier) {
+            while queue.next_is(LogicOrdinal::Period) {
+                if !queue.next_is(LogicOrdinal::Identifier) {
+                    return false;
+                }
+            }
+            true
+        } else {
+            Lson::raw_check(queue)
+        }
     }
     
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         if queue.next_is(LogicOrdinal::Identifier) {
-            if let Some(LibrettoLogicToken::Identifier(value)) = queue.pop() {
-                Some(Self::Variable(value))
+            let Some(LibrettoLogicToken::Identifier(value)) = queue.pop() else {
+                return None;
+            };
+            let mut fields = Vec::new();
+            while queue.pop_if_next_is(LogicOrdinal::Period).is_some() {
+                let Some(LibrettoLogicToken::Identifier(field)) = queue.pop() else {
+                    return None;
+                };
+                fields.push(field);
+            }
+            if fields.is_empty() {
+                Some(Self::Variable(value, start))
             } else {
-                None
+                Some(Self::Member(value, fields.join("."), start.to(queue.last_span())))
             }
         } else {
             if let Some(lson) = Lson::parse(queue, compile_time) {
-                Some(Self::Literal(lson))
+                Some(Self::Literal(lson, start.to(queue.last_span())))
             } else {
                 None
             }
@@ -44,10 +78,11 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicValue {
 
     fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
         match self {
-            LogicValue::Literal(lson) => lson.validate(compile_time),
-            LogicValue::Variable(value) => {
-                return compile_time.get_variable_type(value);
+            LogicValue::Literal(lson, _) => lson.validate(compile_time),
+            LogicValue::Variable(value, span) => {
+                return compile_time.use_variable(value, *span);
             },
+            LogicValue::Member(value, path, span) => compile_time.use_field(value, path, *span),
         }
     }
 }
@@ -55,8 +90,10 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicValue {
 impl LibrettoEvaluator for LogicValue {
     fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
         match self {
-            LogicValue::Literal(value) => Ok(value.clone()),
-            LogicValue::Variable(ident) => Ok(runtime.get_data(ident).clone()),
+            LogicValue::Literal(value, _) => Ok(value.clone()),
+            LogicValue::Variable(ident, _) => Ok(runtime.get_data(ident).clone()),
+            // A field that isn't there reads as none, the same as a variable that isn't there.
+            LogicValue::Member(ident, path, _) => Ok(runtime.get_data(ident).pointer(path).cloned().unwrap_or_default()),
         }
     }
 }
@@ -72,9 +109,11 @@ type ArrayTerm<'a> = CommaSeparatedList<'a, Lson, LibrettoLogicToken>;
 impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for Lson {
     fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
         if queue.next_is(LogicOrdinal::LeftCurlyBracket) {
+            queue.next_is(LogicOrdinal::RightCurlyBracket) ||
             ObjectTerm::<'a>::raw_check(queue) &&
             queue.next_is(LogicOrdinal::RightCurlyBracket)
         } else if queue.next_is(LogicOrdinal::LeftBracket) {
+            queue.next_is(LogicOrdinal::RightBracket) ||
             ArrayTerm::<'a>::raw_check(queue) &&
             queue.next_is(LogicOrdinal::RightBracket)
         } else if queue.next_is(LogicOrdinal::LeftParen) {
@@ -93,6 +132,8 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for Lson {
             LogicOrdinal::NoneLiteral
         ]) {
             true
+        } else if queue.next_is(LogicOrdinal::Sub) {
+            queue.next_is([LogicOrdinal::FloatLiteral, LogicOrdinal::IntLiteral])
         } else {
             false
         }
@@ -101,6 +142,9 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for Lson {
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
         if let Some(token) = queue.pop() {
             if token.check_ordinal(LogicOrdinal::LeftCurlyBracket) {
+                if queue.pop_if_next_is(LogicOrdinal::RightCurlyBracket).is_some() {
+                    return Some(Lson::Struct(LsonMap::new()));
+                }
                 let pairs = parse_ast!(
                     ObjectTerm::<'a>,
                     queue,
@@ -109,13 +153,16 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for Lson {
                 if !queue.pop_and_check_if(LogicOrdinal::RightCurlyBracket) {
                     // Maybe handle error
                 }
-                let data: HashMap<String, Lson> = pairs
+                let data: LsonMap = pairs
                     .values()
                     .iter()
                     .map(|e| (e.key().to_string(), e.value().clone()))
                     .collect();
                 Some(Lson::Struct(data))
             } else if token.check_ordinal(LogicOrdinal::LeftBracket) {
+                if queue.pop_if_next_is(LogicOrdinal::RightBracket).is_some() {
+                    return Some(Lson::Array(Vec::new()));
+                }
                 let pairs = parse_ast!(
                     ArrayTerm::<'a>,
                     queue,
@@ -134,15 +181,17 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for Lson {
                     LibrettoLogicToken::BoolLiteral(value) => {
                         Some(Lson::Bool(value))
                     }
-                    LibrettoLogicToken::FloatLiteral(value) => {
-                        Some(Lson::Float(value))
-                    }
-                    LibrettoLogicToken::IntLiteral(value) => {
-                        Some(Lson::Int(value))
+                    LibrettoLogicToken::FloatLiteral(_) | LibrettoLogicToken::IntLiteral(_) => {
+                        number_literal(token, false, queue.last_span(), compile_time)
                     }
                     LibrettoLogicToken::NoneLiteral => {
                         Some(Lson::None)
                     }
+                    LibrettoLogicToken::Sub => {
+                        let start = queue.last_span();
+                        let token = queue.pop()?;
+                        number_literal(token, true, start.to(queue.last_span()), compile_time)
+                    }
                     _ => None,
                 }
             }
@@ -170,8 +219,44 @@ impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for Lson {
     }
 }
 
+/// Makes the value of a number literal, negated if it had a `-` in front of it. The lexer only
+/// reads magnitudes, so the sign is applied here, which is what lets `-9223372036854775808` be
+/// written even though `9223372036854775808` is too large for an int. An int out of range is
+/// reported and read as `0`, so that the rest of the script can still be parsed.
+fn number_literal(token: LibrettoLogicToken, negative: bool, span: Span, compile_time : &mut LibrettoCompiletime) -> Option<Lson> {
+    let value = match token {
+        LibrettoLogicToken::IntLiteral(magnitude) if negative => 0i64.checked_sub_unsigned(magnitude),
+        LibrettoLogicToken::IntLiteral(magnitude) => i64::try_from(magnitude).ok(),
+        LibrettoLogicToken::FloatLiteral(value) => return Some(Lson::Float(if negative { -value } else { value })),
+        _ => return None,
+    };
+    let value = value.unwrap_or_else(|| {
+        compile_time.push_error(LibrettoCompileErrorKind::LiteralOutOfRange(LsonType::Int), span);
+        0
+    });
+    Some(Lson::Int(value))
+}
+
+/// Reads a value written in the literal syntax of the logic language, like the output of the
+/// `Display` of `Lson`. Only literals are read: variables and operators are not.
+impl FromStr for Lson {
+    type Err = LsonError;
+
+    fn from_str(source: &str) -> Result<Self, LsonError> {
+        let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer(source));
+        let mut compile_time = LibrettoCompiletime::default();
+        if Lson::raw_check(&mut queue) && queue.is_exhausted() {
+            queue.reset();
+            if let Some(value) = Lson::parse(&mut queue, &mut compile_time).filter(|_| compile_time.error_count() == 0) {
+                return Ok(value);
+            }
+        }
+        Err(LsonError::NotALiteral(source.to_string()))
+    }
+}
+
 //==================================================================================================
-//          Lson Parsable
+//          Lson Type Parsable
 //==================================================================================================
 
 impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LsonType {
@@ -199,10 +284,8 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for LsonType {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use crate::{
-        lson::{Lson, LsonType},
+        lson::{Lson, LsonMap, LsonType},
         parse::test_util::*,
     };
 
@@ -222,10 +305,105 @@ mod tests {
         assert_eq!(ast, Lson::None);
 
         let ast = parse_expr::<Lson>("{obj : {key : \"value\"}}");
-        assert_eq!(ast, Lson::Struct(HashMap::from([("obj".to_string(), Lson::Struct(HashMap::from([("key".to_string(), "value".into())])))])));
+        assert_eq!(ast, Lson::Struct(LsonMap::from([("obj".to_string(), Lson::Struct(LsonMap::from([("key".to_string(), "value".into())])))])));
 
         let ast = parse_expr::<Lson>("[true, false]");
         assert_eq!(ast, Lson::Array(vec![Lson::Bool(true), Lson::Bool(false)]));
+
+        let Lson::Struct(fields) = parse_expr::<Lson>("{ zeta : 1, alpha : 2, mid : 3 }") else { panic!() };
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["zeta", "alpha", "mid"]);
+    }
+
+    #[test]
+    fn parse_lson_literals() {
+        assert_eq!(parse_expr::<Lson>("[-1, -2.5]"), Lson::Array(vec![Lson::Int(-1), Lson::Float(-2.5)]));
+        assert_eq!(parse_expr::<Lson>("{ a : [], b : {} }"), Lson::Struct(LsonMap::from([
+            ("a".to_string(), Lson::Array(vec![])),
+            ("b".to_string(), Lson::Struct(LsonMap::new())),
+        ])));
+        assert_eq!(parse_expr::<Lson>("{ \"max hp\" : 3 }")["max hp"], 3);
+
+        assert_eq!("{ if : 1 }".parse::<Lson>(), Err(LsonError::NotALiteral("{ if : 1 }".to_string())));
+        assert!("[1, 2] 3".parse::<Lson>().is_err());
+        assert!("foo".parse::<Lson>().is_err());
+    }
+
+    #[test]
+    fn parse_string_literals() {
+        assert_eq!(parse_expr::<Lson>(r#""say \"hi\"\n""#), Lson::from("say \"hi\"\n"));
+        assert_eq!(parse_expr::<Lson>(r#""\t\u{48}\u{1F600}\\""#), Lson::from("\tH\u{1F600}\\"));
+        assert_eq!(parse_expr::<Lson>(r#"r"C:\dir\n""#), Lson::from("C:\\dir\\n"));
+        assert_eq!(parse_expr::<Lson>(r##"r#"a "b" c"#"##), Lson::from("a \"b\" c"));
+        assert_eq!(parse_expr::<Lson>(r###"r##"a "#b"# c"##"###), Lson::from("a \"#b\"# c"));
+        assert_eq!(parse_expr::<Lson>(r#"["a\"", r"b"]"#), crate::lson!(["a\"", "b"]));
+
+        for source in [r#""\q""#, r#""\u{110000}""#, r#""\u{41""#, r#""\u41""#] {
+            assert_eq!(LibrettoLogicToken::lexer(source).next(), Some(LibrettoLogicToken::Error), "{source}");
+        }
+    }
+
+    #[test]
+    fn parse_number_literals() {
+        assert_eq!(parse_expr::<Lson>("0x1F"), Lson::Int(31));
+        assert_eq!(parse_expr::<Lson>("0b1010"), Lson::Int(10));
+        assert_eq!(parse_expr::<Lson>("1_000_000"), Lson::Int(1_000_000));
+        assert_eq!(parse_expr::<Lson>("0xFFFF_FFFF"), Lson::Int(0xFFFF_FFFF));
+        assert_eq!(parse_expr::<Lson>("1e-3"), Lson::Float(1e-3));
+        assert_eq!(parse_expr::<Lson>("2.5E+2"), Lson::Float(250.0));
+        assert_eq!(parse_expr::<Lson>(".5"), Lson::Float(0.5));
+        assert_eq!(parse_expr::<Lson>("1_000.25"), Lson::Float(1000.25));
+        assert_eq!(parse_expr::<Lson>("9223372036854775807"), Lson::Int(i64::MAX));
+        assert_eq!(parse_expr::<Lson>("-9223372036854775808"), Lson::Int(i64::MIN));
+        assert_eq!(parse_expr::<Lson>("-0x10"), Lson::Int(-16));
+        assert_eq!("-.25".parse::<Lson>(), Ok(Lson::Float(-0.25)));
+        assert_eq!(Lson::Int(i64::MIN).to_string().parse::<Lson>(), Ok(Lson::Int(i64::MIN)));
+
+        // A `.` followed by a digit starts a float, even right after a name, so `player.5` is not
+        // a field read.
+        assert_eq!(
+            LibrettoLogicToken::lexer("player.5").collect::<Vec<_>>(),
+            [LibrettoLogicToken::Identifier("player".to_string()), LibrettoLogicToken::FloatLiteral(0.5)],
+        );
+        assert_eq!(
+            LibrettoLogicToken::lexer("player.name").collect::<Vec<_>>(),
+            [
+                LibrettoLogicToken::Identifier("player".to_string()),
+                LibrettoLogicToken::Period,
+                LibrettoLogicToken::Identifier("name".to_string()),
+            ],
+        );
+    }
+
+    #[test]
+    fn number_literals_out_of_range() {
+        for source in ["0x1_0000_0000_0000_0000", "99999999999999999999999", "1e400", ".1e400"] {
+            let mut lexer = LibrettoLogicToken::lexer(source);
+            assert_eq!(lexer.next(), Some(LibrettoLogicToken::Error), "{source}");
+            assert_eq!(lexer.span(), 0..source.len(), "{source}");
+        }
+
+        let source = "start { | <let gold = [1, 18446744073709551616];> \"Rich.\" }";
+        let diagnostics = crate::compiler::compile(source).err().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.iter().next().unwrap().span, Span::new(26, 46, 1, 27));
+
+        for source in ["9223372036854775808", "-9223372036854775809"] {
+            let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer(source));
+            let mut compile_time = LibrettoCompiletime::default();
+            assert_eq!(Lson::checked_parse(&mut queue, &mut compile_time), Some(Lson::Int(0)), "{source}");
+            assert_eq!(compile_time.error_count(), 1, "{source}");
+            let error = &compile_time.errors()[0];
+            assert_eq!(error.kind, LibrettoCompileErrorKind::LiteralOutOfRange(LsonType::Int));
+            assert_eq!(error.span, Span::new(0, source.len(), 1, 1));
+        }
+
+        let source = "start { | <if 9223372036854775808 != 0> \"Big.\" }";
+        let diagnostics = crate::compiler::compile(source).err().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.iter().next().unwrap().message, "The number is too large to be stored as a int.");
+
+        assert!("9223372036854775808".parse::<Lson>().is_err());
+        assert!(LibrettoLogicToken::lexer("0x_").all(|token| token == LibrettoLogicToken::Error));
     }
 
     #[test]
@@ -244,6 +422,7 @@ mod tests {
         check_expr::<LogicValue>("{ key : false, test : false }", 9);
         check_expr::<LogicValue>("test", 1);
         check_expr::<LogicValue>("(2+2)", 5);
+        check_expr::<LogicValue>("player.stats.hp", 5);
 
         // check_expr("3.14");
         // check_expr("\"Hello World\"");
@@ -258,11 +437,65 @@ mod tests {
         println!("{:?}", ast)
     }
 
+    #[test]
+    fn parse_logic_value_span() {
+        let ast = parse_expr::<LogicValue>("[true, false]");
+        assert_eq!(ast.span(), Span::new(0, 13, 1, 1));
+
+        let ast = parse_expr::<LogicValue>("test");
+        assert_eq!(ast, LogicValue::Variable("test".to_string(), Span::new(0, 4, 1, 1)));
+
+        let ast = parse_expr::<LogicValue>("player.stats.hp");
+        assert_eq!(ast, LogicValue::Member("player".to_string(), "stats.hp".to_string(), Span::new(0, 15, 1, 1)));
+    }
+
     #[test]
     fn validate_logic_value() {
         validate_expr::<LogicValue>("3", 0, LsonType::Int);
         validate_expr::<LogicValue>("[true, false]", 0, LsonType::Array);
         validate_expr::<LogicValue>("foo", 0, LsonType::Float);
         validate_expr::<LogicValue>("test", 0, LsonType::None);
+        validate_expr::<LogicValue>("bar.baz", 1, LsonType::None);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn validate_logic_value_member() {
+        use crate::lson::IntoLson;
+
+        #[derive(IntoLson)]
+        struct Stats {
+            hp: i64,
+        }
+
+        #[derive(IntoLson)]
+        struct Player {
+            name: String,
+            stats: Stats,
+        }
+
+        let validate = |source| {
+            let mut queue = LibrettoTokenQueue::from(LibrettoLogicToken::lexer(source));
+            let mut compile_time = LibrettoCompiletime::default()
+                .with_schema("player", Player::lson_schema())
+                .with_schema("any", Lson::lson_schema());
+            let lson_type = LogicValue::checked_parse(&mut queue, &mut compile_time).unwrap().validate(&mut compile_time);
+            (lson_type, compile_time.errors().iter().map(|error| error.kind.code()).collect::<Vec<_>>())
+        };
+        assert_eq!(validate("player"), (LsonType::Struct, vec![]));
+        assert_eq!(validate("player.name"), (LsonType::String, vec![]));
+        assert_eq!(validate("player.stats.hp"), (LsonType::Int, vec![]));
+        assert_eq!(validate("player.nmae"), (LsonType::None, vec!["E0025"]));
+        assert_eq!(validate("player.name.first"), (LsonType::None, vec!["E0025"]));
+        assert_eq!(validate("any.thing"), (LsonType::None, vec![]));
+
+        let mut runtime = LibrettoRuntime::with_data([(String::from("player"), Player {
+            name: "Ada".to_string(),
+            stats: Stats { hp: 3 },
+        }.into_lson())]);
+        let value = parse_expr::<LogicValue>("player.stats.hp");
+        assert_eq!(value.evaluate(&mut runtime).unwrap(), Lson::Int(3));
+        let value = parse_expr::<LogicValue>("player.stats.mp");
+        assert_eq!(value.evaluate(&mut runtime).unwrap(), Lson::None);
     }
 }
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::{LibrettoCompileErrorKind, LibrettoCompiletime},
+    lexer::{LibrettoLogicToken, LibrettoTokenQueue, LogicOrdinal},
+    lson::{Lson, LsonType},
+    parse_ast,
+    runtime::{dialog::LibrettoStep, LibrettoEvaluator, LibrettoRuntime, LibrettoRuntimeErrorKind, LibrettoRuntimeResult},
+    span::Span,
+};
+
+use super::{logic_expr::LogicExpr, LibrettoParsable};
+
+//==================================================================================================
+//          Logic Yield Statement
+//==================================================================================================
+
+/// Hands control back to the host. `yield` and `yield payload` pause the dialog until the host
+/// steps it again, while `wait seconds` asks the host to wait before doing so. A pause made in a
+/// line is returned right after the line is presented.
+#[derive(Serialize, Deserialize)]
+pub enum LogicYieldStatement {
+    Yield(Option<LogicExpr>, Span),
+    Wait(LogicExpr, Span),
+}
+
+impl LogicYieldStatement {
+    pub fn span(&self) -> Span {
+        match self {
+            LogicYieldStatement::Yield(_, span) | LogicYieldStatement::Wait(_, span) => *span,
+        }
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoLogicToken> for LogicYieldStatement {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
+        if queue.next_is(LogicOrdinal::Wait) {
+            return LogicExpr::raw_check(queue);
+        }
+
+        if !queue.next_is(LogicOrdinal::Yield) {
+            return false;
+        }
+        let start = queue.cursor();
+        if !LogicExpr::raw_check(queue) {
+            queue.rewind_to(start);
+        }
+        true
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        queue.reset();
+        let start = queue.next_span();
+        if queue.pop_if_next_is(LogicOrdinal::Wait).is_some() {
+            let seconds = parse_ast!(LogicExpr, queue, compile_time);
+            return Some(LogicYieldStatement::Wait(seconds, start.to(queue.last_span())));
+        }
+
+        queue.pop_if_next_is(LogicOrdinal::Yield)?;
+        let payload = if LogicExpr::check(queue) {
+            Some(parse_ast!(LogicExpr, queue, compile_time))
+        } else {
+            None
+        };
+        Some(LogicYieldStatement::Yield(payload, start.to(queue.last_span())))
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        match self {
+            LogicYieldStatement::Yield(Some(payload), _) => {
+                payload.validate(compile_time);
+            }
+            LogicYieldStatement::Yield(None, _) => {}
+            LogicYieldStatement::Wait(seconds, _) => {
+                let seconds_type = seconds.validate(compile_time);
+                if !matches!(seconds_type, LsonType::Int | LsonType::Float) {
+                    compile_time.push_error(LibrettoCompileErrorKind::WaitNotNumber(seconds_type.to_string()), seconds.span());
+                }
+            }
+        }
+        LsonType::None
+    }
+}
+
+impl LibrettoEvaluator for LogicYieldStatement {
+    fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult {
+        let step = match self {
+            LogicYieldStatement::Yield(payload, _) => {
+                let payload = match payload {
+                    Some(payload) => payload.evaluate(runtime)?,
+                    None => Lson::None,
+                };
+                LibrettoStep::Yielded(payload)
+            }
+            LogicYieldStatement::Wait(seconds, _) => match seconds.evaluate(runtime)? {
+                Lson::Int(value) => LibrettoStep::Waiting(value as f64),
+                Lson::Float(value) => LibrettoStep::Waiting(value),
+                other => return Err(LibrettoRuntimeErrorKind::WaitNotNumber(other.get_type().to_string()).at(seconds.span())),
+            },
+        };
+        runtime.pause(step);
+        Ok(Lson::None)
+    }
+}
+
+//==================================================================================================
+//          Logic Yield Statement Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::test_util::*;
+
+    use super::*;
+
+    #[test]
+    fn check_yield_stmt() {
+        check_expr::<LogicYieldStatement>("yield", 1);
+        check_expr::<LogicYieldStatement>("yield \"shop\"", 2);
+        check_expr::<LogicYieldStatement>("wait 1.5", 2);
+    }
+
+    #[test]
+    fn parse_yield_stmt() {
+        assert!(matches!(parse_expr::<LogicYieldStatement>("yield"), LogicYieldStatement::Yield(None, _)));
+        assert!(matches!(parse_expr::<LogicYieldStatement>("yield foo"), LogicYieldStatement::Yield(Some(_), _)));
+        let ast = parse_expr::<LogicYieldStatement>("wait 2");
+        assert!(matches!(ast, LogicYieldStatement::Wait(_, _)));
+        assert_eq!(ast.span(), Span::new(0, 6, 1, 1));
+    }
+
+    #[test]
+    fn validate_yield_stmt() {
+        validate_expr::<LogicYieldStatement>("yield", 0, LsonType::None);
+        validate_expr::<LogicYieldStatement>("wait foo", 0, LsonType::None);
+        validate_expr::<LogicYieldStatement>("wait bar", 1, LsonType::None);
+    }
+
+    #[test]
+    fn evaluate_yield_stmt() {
+        let mut runtime = evaluate_expr::<LogicYieldStatement>("yield \"shop\"", Lson::None);
+        assert_eq!(runtime.take_pause(), Some(LibrettoStep::Yielded(Lson::String("shop".to_string()))));
+        let mut runtime = evaluate_expr::<LogicYieldStatement>("wait 2", Lson::None);
+        assert_eq!(runtime.take_pause(), Some(LibrettoStep::Waiting(2.0)));
+    }
+}
@@ -0,0 +1,525 @@
+use serde::{Deserialize, Serialize};
+use crate::{
+    compiler::{LibrettoCompileErrorKind, LibrettoCompileWarningKind, LibrettoCompiletime},
+    lexer::{LibrettoToken, LibrettoTokenQueue, TokenOrdinal},
+    lson::LsonType,
+    runtime::dialog::DialogCursor,
+    span::Span,
+};
+
+use super::{dialog::Dialog, dialog_response::ResponseTarget, dialog_target::DialogTarget, logic_block::LogicBlock, LibrettoParsable};
+
+//==================================================================================================
+//          Script
+//==================================================================================================
+
+/// A whole libretto file. Logic blocks that are written outside of a dialog make up the preamble,
+/// which is run once into the root scope when the script starts. Tags written outside of a dialog
+/// are attributes that set the level of a lint for the whole script, like `#deny(unused_variable)`.
+#[derive(Serialize, Deserialize)]
+pub struct Script {
+    preamble: Vec<LogicBlock>,
+    dialogs: Vec<Dialog>,
+    span: Span,
+}
+
+impl Script {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn preamble(&self) -> &Vec<LogicBlock> {
+        &self.preamble
+    }
+
+    pub fn dialogs(&self) -> &Vec<Dialog> {
+        &self.dialogs
+    }
+
+    pub fn dialog_index(&self, name: &str) -> Option<usize> {
+        self.dialogs.iter().position(|dialog| dialog.name() == name)
+    }
+
+    /// Checks that no call can be reached again from the dialog it calls. Every time such a call is
+    /// made, it adds to the return stack, and nothing guarantees the stack ever shrinks back down.
+    fn validate_calls(&self, compile_time : &mut LibrettoCompiletime) {
+        let edges: Vec<Vec<&DialogTarget>> = self.dialogs.iter()
+            .map(|dialog| dialog.targets().filter(|target| target.resolved().is_some()).collect())
+            .collect();
+
+        for (from, targets) in edges.iter().enumerate() {
+            for target in targets.iter().filter(|target| target.is_call()) {
+                let mut visited = vec![false; self.dialogs.len()];
+                let mut stack = vec![target.resolved().unwrap().dialog];
+                while let Some(dialog) = stack.pop() {
+                    if std::mem::replace(&mut visited[dialog], true) {
+                        continue;
+                    }
+                    stack.extend(edges[dialog].iter().map(|target| target.resolved().unwrap().dialog));
+                }
+                if visited[from] {
+                    compile_time.push_error(LibrettoCompileErrorKind::RecursiveCall(target.to_string()), target.span());
+                }
+            }
+        }
+    }
+
+    /// Warns about named dialogs that no target leads to from the first dialog in the script, which
+    /// is where a script is expected to start.
+    fn validate_reachable(&self, compile_time : &mut LibrettoCompiletime) {
+        let Some(entry) = self.dialogs.first() else {
+            return;
+        };
+        let mut visited = vec![false; self.dialogs.len()];
+        let mut stack = vec![0];
+        while let Some(dialog) = stack.pop() {
+            if std::mem::replace(&mut visited[dialog], true) {
+                continue;
+            }
+            stack.extend(self.dialogs[dialog].targets().filter_map(|target| target.resolved()).map(|cursor| cursor.dialog));
+        }
+
+        for (dialog, _) in self.dialogs.iter().zip(visited).filter(|(dialog, visited)| !visited && !dialog.is_inline()) {
+            compile_time.push_warning(LibrettoCompileWarningKind::UnreachableDialog(dialog.name().to_string(), entry.name().to_string()), dialog.span());
+        }
+    }
+
+    /// Warns about responses whose target has no lines to show once it is chosen.
+    fn validate_responses(&self, compile_time : &mut LibrettoCompiletime) {
+        let responses = self.dialogs.iter().flat_map(|dialog| dialog.lines()).flat_map(|line| line.responses());
+        for response in responses {
+            let ResponseTarget::Dialog(target) = response.target() else {
+                continue;
+            };
+            if target.resolved().is_some_and(|cursor| cursor.line >= self.dialogs[cursor.dialog].lines().len()) {
+                compile_time.push_warning(LibrettoCompileWarningKind::ResponseLeadsNowhere(response.text().to_string()), response.span());
+            }
+        }
+    }
+}
+
+impl<'a> LibrettoParsable<'a, LibrettoToken<'a>> for Script {
+    fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>) -> bool {
+        while !queue.is_exhausted() {
+            if !(queue.next_is([TokenOrdinal::Logic, TokenOrdinal::Tag]) || Dialog::raw_check(queue)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoToken<'a>>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let mut preamble = Vec::new();
+        let mut dialogs = Vec::new();
+
+        queue.reset();
+        let start = queue.next_span();
+        while !queue.is_exhausted() {
+            queue.clear_failure();
+            if let Some(LibrettoToken::Logic(mut inner)) = queue.pop_if_next_is(TokenOrdinal::Logic) {
+                preamble.extend(LogicBlock::parse(&mut inner, compile_time));
+            } else if let Some(LibrettoToken::Tag(attribute)) = queue.pop_if_next_is(TokenOrdinal::Tag) {
+                if !compile_time.lints_mut().apply_attribute(&attribute) {
+                    compile_time.push_error(LibrettoCompileErrorKind::UnknownAttribute(attribute), queue.last_span());
+                }
+            } else if let Some(dialog) = Dialog::parse(queue, compile_time) {
+                dialogs.push(dialog);
+            } else {
+                // The error has been pushed, so skip past the end of the broken dialog.
+                queue.skip_until(TokenOrdinal::RightCurlyBracket);
+                queue.pop();
+            }
+            queue.reset();
+        }
+
+        let inline: Vec<Dialog> = dialogs.iter_mut().flat_map(|dialog| dialog.take_inline_dialogs()).collect();
+        dialogs.extend(inline);
+
+        let resolved: Vec<Vec<Option<DialogCursor>>> = dialogs.iter().enumerate()
+            .map(|(index, dialog)| dialog.targets().map(|target| target.find(&dialogs, Some(index)).ok()).collect())
+            .collect();
+        for (dialog, resolved) in dialogs.iter_mut().zip(resolved) {
+            for (target, cursor) in dialog.targets_mut().zip(resolved) {
+                target.set_resolved(cursor);
+            }
+        }
+
+        Some(Script { preamble, dialogs, span: start.to(queue.last_span()) })
+    }
+
+    fn validate(&self, compile_time : &mut LibrettoCompiletime) -> LsonType {
+        for block in self.preamble.iter() {
+            block.validate(compile_time);
+        }
+        for (index, dialog) in self.dialogs.iter().enumerate() {
+            dialog.validate(compile_time);
+            for target in dialog.targets() {
+                if let Err(error) = target.find(&self.dialogs, Some(index)) {
+                    compile_time.push_error(error, target.span());
+                }
+            }
+        }
+        self.validate_calls(compile_time);
+        self.validate_reachable(compile_time);
+        self.validate_responses(compile_time);
+        compile_time.report_declarations(1);
+        LsonType::None
+    }
+}
+
+//==================================================================================================
+//          Script Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use logos::Logos;
+
+    use crate::{compiler::{LibrettoLint, LibrettoLintConfig, LintLevel}, lson::Lson, parse::test_util::*, runtime::{dialog::LibrettoStep, LibrettoRuntime, LibrettoRuntimeError, LibrettoRuntimeErrorKind, LibrettoSaveState}};
+
+    use super::*;
+
+    const SCRIPT: &str = r#"
+        <let visits = 0;>
+
+        start {
+            | :Jake <let greeting = "Hello";> <visits = visits + 1> "Hello there." #happy
+            | :Jake <let greeting = "Bye";> "Goodbye."
+        }
+
+        <let mood = "calm";>
+    "#;
+
+    #[test]
+    fn check_script() {
+        check_dialog::<Script>("<let test = 2;> start { | \"Hello.\" }", 6);
+    }
+
+    #[test]
+    fn parse_script() {
+        let ast = parse_dialog::<Script>(SCRIPT);
+        assert_eq!(ast.preamble().len(), 2);
+        assert_eq!(ast.dialogs().len(), 1);
+        assert_eq!(ast.dialog_index("start"), Some(0));
+    }
+
+    #[test]
+    fn validate_script() {
+        validate_dialog::<Script>(SCRIPT, 0);
+        validate_dialog::<Script>("start { | <let test = 2;> } <test = 3>", 1);
+    }
+
+    #[test]
+    fn recover_script() {
+        let source = r#"
+            <let = 2; let ok = 1;>
+
+            start {
+                | "Hello." "Again."
+                | :Jake <let count = ;> "Hi."
+                | "Fine." foo
+                | "Still parsed." -- 
+            }
+
+            broken
+            skipped {
+                | "Skipped with the broken dialog."
+            }
+
+            next {
+                | "Also parsed."
+            }
+        "#;
+        let mut queue = LibrettoTokenQueue::from(LibrettoToken::lexer(source));
+        let mut compile_time = LibrettoCompiletime::default();
+        let ast = Script::parse(&mut queue, &mut compile_time).unwrap();
+
+        let messages: Vec<String> = compile_time.errors().iter().map(|error| error.to_string()).collect();
+        assert_eq!(messages, vec![
+            "Expected a name, but found `=`.",
+            "A line can only have one quote.",
+            "Expected `!`, `-`, a name, `{`, `[`, `(`, a string, a bool, a float, an int or `none`, but found `;`.",
+            "Expected `->`, `--`, a logic block, a quote, a tag, `}` or `|`, but found `foo`.",
+            "Expected `call`, a name or a path, but found `}`.",
+            "Expected `{`, but found `skipped`.",
+        ]);
+        assert_eq!(compile_time.errors()[0].span.line, 2);
+        assert_eq!(compile_time.errors()[3].span.line, 7);
+
+        assert_eq!(ast.preamble().len(), 1);
+        assert_eq!(ast.dialogs().len(), 2);
+        assert_eq!(ast.dialogs()[0].lines().len(), 1);
+        assert_eq!(ast.dialogs()[1].name(), "next");
+    }
+
+    #[test]
+    fn lint_script() {
+        let source = r#"
+            <request standing : int; request roster : array; let visits = 0;>
+
+            start {
+                | <let visits = 1;> <if true> "Always." <else> "Never."
+                | <let _ignored = 2;> "Standing: " <standing>
+                -> "Leave." empty
+                -> "Stay." start
+            }
+
+            empty {}
+
+            forgotten {
+                | "Nobody comes here."
+            }
+        "#;
+        let warnings = |source: &str, compile_time: &mut LibrettoCompiletime| {
+            let mut queue = LibrettoTokenQueue::from(LibrettoToken::lexer(source));
+            Script::parse(&mut queue, compile_time).unwrap().validate(compile_time);
+            compile_time.warnings().iter().map(|warning| warning.kind.clone()).collect::<Vec<_>>()
+        };
+
+        let mut compile_time = LibrettoCompiletime::default();
+        let found = warnings(source, &mut compile_time);
+        assert_eq!(compile_time.error_count(), 0);
+        assert_eq!(found.len(), 7);
+        for expected in [
+            LibrettoCompileWarningKind::ShadowedVariable("visits".to_string()),
+            LibrettoCompileWarningKind::ConstantCondition,
+            LibrettoCompileWarningKind::UnusedVariable("visits".to_string()),
+            LibrettoCompileWarningKind::ResponseLeadsNowhere("Leave.".to_string()),
+            LibrettoCompileWarningKind::UnreachableDialog("forgotten".to_string(), "start".to_string()),
+            LibrettoCompileWarningKind::UnusedRequest("roster".to_string()),
+        ] {
+            assert!(found.contains(&expected), "{:?} was not reported", expected);
+        }
+
+        let mut compile_time = LibrettoCompiletime::default();
+        compile_time.set_lints(LibrettoLintConfig::default().with_default(LintLevel::Deny).allow(LibrettoLint::UnusedVariable));
+        let found = warnings(&format!("#allow(unreachable_dialog) #warn(constant_condition) {}", source), &mut compile_time);
+        assert_eq!(found.len(), 4);
+        assert_eq!(compile_time.error_count(), 3);
+
+        let mut compile_time = LibrettoCompiletime::default();
+        warnings("#allow(everything) start { | \"Hello.\" }", &mut compile_time);
+        assert_eq!(compile_time.errors()[0].kind, LibrettoCompileErrorKind::UnknownAttribute("allow(everything)".to_string()));
+    }
+
+    #[test]
+    fn evaluate_script() {
+        let ast = parse_dialog::<Script>(SCRIPT);
+        let mut runtime = LibrettoRuntime::default();
+        runtime.start(&ast, "start").unwrap();
+        assert_eq!(runtime.get_data("visits"), Lson::Int(0));
+        assert_eq!(runtime.get_data("mood"), Lson::String("calm".to_string()));
+
+        let line = runtime.next_line(&ast).unwrap().unwrap();
+        assert_eq!(line.text, "Hello there.");
+        assert_eq!(runtime.get_data("visits"), Lson::Int(1));
+        assert!(!runtime.has_data("greeting"));
+
+        let line = runtime.next_line(&ast).unwrap().unwrap();
+        assert_eq!(line.text, "Goodbye.");
+        assert!(runtime.next_line(&ast).unwrap().is_none());
+    }
+
+    #[test]
+    fn evaluate_script_responses() {
+        let ast = parse_dialog::<Script>(r#"
+            <let member = false;>
+
+            start {
+                | :Jake <if member> "Welcome back." <else> "Who are you?"
+                    -> "A friend." { | <member = true> "Then come in." }
+                    -> "Nobody." leave
+            }
+
+            leave {
+                | "Goodbye."
+            }
+        "#);
+        assert_eq!(ast.dialogs().len(), 3);
+
+        let mut runtime = LibrettoRuntime::default();
+        runtime.start(&ast, "start").unwrap();
+        let line = runtime.next_line(&ast).unwrap().unwrap();
+        assert_eq!(line.text, "Who are you?");
+        let responses: Vec<&str> = line.responses.iter().map(|response| response.text.as_str()).collect();
+        assert_eq!(responses, vec!["A friend.", "Nobody."]);
+        assert!(runtime.next_line(&ast).is_err());
+        assert!(runtime.choose(2).is_err());
+
+        runtime.choose(0).unwrap();
+        let line = runtime.next_line(&ast).unwrap().unwrap();
+        assert_eq!(line.text, "Then come in.");
+        assert_eq!(runtime.get_data("member"), Lson::Bool(true));
+        assert!(runtime.next_line(&ast).unwrap().is_none());
+
+        runtime.start(&ast, "start").unwrap();
+        runtime.next_line(&ast).unwrap();
+        runtime.choose(1).unwrap();
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Goodbye.");
+    }
+
+    #[test]
+    fn evaluate_script_guarded_responses() {
+        let ast = parse_dialog::<Script>(r#"
+            start {
+                | "Where to?"
+                    -> <if bar else "Members only."> "Upstairs." upstairs
+                    -> "Outside." outside
+            }
+            upstairs { | "Up we go." }
+            outside { | "Out we go." }
+        "#);
+
+        let mut runtime = LibrettoRuntime::with_data([(String::from("bar"), Lson::Bool(false))]);
+        runtime.start(&ast, "start").unwrap();
+        let line = runtime.next_line(&ast).unwrap().unwrap();
+        assert!(!line.responses[0].available);
+        assert!(matches!(runtime.choose(0), Err(LibrettoRuntimeError { kind: LibrettoRuntimeErrorKind::ResponseUnavailable(0), .. })));
+        runtime.choose(1).unwrap();
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Out we go.");
+    }
+
+    #[test]
+    fn validate_script_targets() {
+        validate_dialog::<Script>("start { | \"Hi.\" -- shop.greeting } shop { | @greeting \"Welcome.\" }", 0);
+        validate_dialog::<Script>("start { | \"Hi.\" -- nowhere -> \"Ok.\" shop.nothing } shop { | \"Welcome.\" }", 2);
+        validate_dialog::<Script>("start { | \"Hi.\" -- shop } shop { | \"A\" } shop { | \"B\" }", 1);
+        validate_dialog::<Script>("start { | \"Hi.\" -- shop | @shop \"Here.\" } shop { | \"Welcome.\" }", 1);
+    }
+
+    #[test]
+    fn evaluate_script_diverts() {
+        let ast = parse_dialog::<Script>(r#"
+            start {
+                | "Let's go shopping." -- shop.greeting
+                | "This is never said."
+            }
+
+            shop {
+                | "The shop is closed."
+                | @greeting "Welcome to the shop!" -> "Again." start -> "Browse." browse
+                | @browse "Have a look." -- done
+                | @done "Bye."
+            }
+        "#);
+
+        let mut runtime = LibrettoRuntime::default();
+        runtime.start(&ast, "start").unwrap();
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Let's go shopping.");
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Welcome to the shop!");
+        runtime.choose(1).unwrap();
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Have a look.");
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Bye.");
+        assert!(runtime.next_line(&ast).unwrap().is_none());
+
+        runtime.start(&ast, "shop.done").unwrap();
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Bye.");
+        assert!(matches!(runtime.start(&ast, "shop.missing"), Err(LibrettoRuntimeError { kind: LibrettoRuntimeErrorKind::DialogNotDefined(_), .. })));
+    }
+
+    #[test]
+    fn validate_script_calls() {
+        validate_dialog::<Script>("start { | \"Hi.\" -- call barter | \"Bye.\" } barter { | \"Trade?\" }", 0);
+        validate_dialog::<Script>("start { | \"Hi.\" -- call barter } barter { | \"Trade?\" -- start }", 1);
+        validate_dialog::<Script>("start { | \"Hi.\" -> \"Trade.\" call barter } barter { | \"Trade?\" -- call barter }", 1);
+        validate_dialog::<Script>("start { | \"Hi.\" -- call a } a { | \"A\" -- call b } b { | \"B\" -- call a }", 2);
+    }
+
+    #[test]
+    fn evaluate_script_calls() {
+        let ast = parse_dialog::<Script>(r#"
+            start {
+                | "Want to trade?" -> "Sure." call barter -> "No." leave
+                | "Anything else?" -- call barter.menu
+                | "Goodbye."
+            }
+
+            barter {
+                | "Let me get my things."
+                | @menu "Here is what I have." -- call prices
+            }
+
+            prices { | "Everything is ten gold." }
+            leave { | "Bye." }
+        "#);
+
+        let mut runtime = LibrettoRuntime::default();
+        runtime.start(&ast, "start").unwrap();
+        runtime.next_line(&ast).unwrap();
+        runtime.choose(0).unwrap();
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Let me get my things.");
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Here is what I have.");
+        assert_eq!(runtime.save().call_depth(), 2);
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Everything is ten gold.");
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Anything else?");
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Here is what I have.");
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Everything is ten gold.");
+        assert_eq!(runtime.next_line(&ast).unwrap().unwrap().text, "Goodbye.");
+        assert!(runtime.next_line(&ast).unwrap().is_none());
+    }
+
+    #[test]
+    fn evaluate_script_save_state() {
+        let ast = parse_dialog::<Script>(r#"
+            <let visits = 0;>
+            start {
+                | <visits = visits + 1> "Let me check." -- call check
+                | "All done."
+            }
+            check { | "Checking..." | "Found it." }
+        "#);
+
+        let mut runtime = LibrettoRuntime::default();
+        runtime.start(&ast, "start").unwrap();
+        runtime.next_line(&ast).unwrap();
+        runtime.next_line(&ast).unwrap();
+        let state = runtime.save();
+        assert_eq!(state.call_depth(), 1);
+
+        let bytes = bincode::serialize(&state).unwrap();
+        let state: LibrettoSaveState = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(state.call_depth(), 1);
+
+        let mut restored = LibrettoRuntime::default();
+        restored.restore(state);
+        assert_eq!(restored.get_data("visits"), Lson::Int(1));
+        assert_eq!(restored.next_line(&ast).unwrap().unwrap().text, "Found it.");
+        assert_eq!(restored.next_line(&ast).unwrap().unwrap().text, "All done.");
+        assert!(restored.next_line(&ast).unwrap().is_none());
+    }
+
+    #[test]
+    fn evaluate_script_steps() {
+        let ast = parse_dialog::<Script>(r#"
+            start {
+                | "Let me think." <wait 1.5>
+                | "Do you want to trade?" -> "Yes." shop -> "No." leave
+            }
+
+            shop {
+                | "Have a look." <yield "show_shop"> <yield>
+                | "Come again!"
+            }
+
+            leave { | "Bye." }
+        "#);
+
+        let mut runtime = LibrettoRuntime::default();
+        runtime.start(&ast, "start").unwrap();
+        assert!(matches!(runtime.step(&ast).unwrap(), LibrettoStep::LineReady(line) if line.text == "Let me think."));
+        assert_eq!(runtime.step(&ast).unwrap(), LibrettoStep::Waiting(1.5));
+        assert!(matches!(runtime.step(&ast).unwrap(), LibrettoStep::ChoicesReady(line) if line.responses.len() == 2));
+        assert!(matches!(runtime.step(&ast), Err(LibrettoRuntimeError { kind: LibrettoRuntimeErrorKind::ChoicePending, .. })));
+
+        runtime.choose(0).unwrap();
+        assert!(matches!(runtime.step(&ast).unwrap(), LibrettoStep::LineReady(line) if line.text == "Have a look."));
+        assert_eq!(runtime.step(&ast).unwrap(), LibrettoStep::Yielded(Lson::String("show_shop".to_string())));
+        let state = bincode::deserialize::<LibrettoSaveState>(&bincode::serialize(&runtime.save()).unwrap()).unwrap();
+        assert_eq!(runtime.step(&ast).unwrap(), LibrettoStep::Yielded(Lson::None));
+        assert!(matches!(runtime.step(&ast).unwrap(), LibrettoStep::LineReady(line) if line.text == "Come again!"));
+        assert_eq!(runtime.step(&ast).unwrap(), LibrettoStep::Finished);
+
+        runtime.restore(state);
+        assert_eq!(runtime.step(&ast).unwrap(), LibrettoStep::Yielded(Lson::None));
+    }
+}
@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use super::LibrettoParsable;
 use crate::{
     lexer::{LibrettoLogicToken, LogicOrdinal, Ordinal, LibrettoTokenQueue},
     lson::LsonType,
-    parse_ast, compiler::LibrettoCompiletime,
+    parse_ast, compiler::LibrettoCompiletime, span::Span,
 };
 use logos::Logos;
 use std::{fmt::Debug, marker::PhantomData};
@@ -11,13 +12,18 @@ use std::{fmt::Debug, marker::PhantomData};
 //          Logic Typed Identifier
 //==================================================================================================
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TypedIdentifier {
     implicit_type: Option<LsonType>,
-    ident: String
+    ident: String,
+    span: Span,
 }
 
 impl TypedIdentifier {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
     pub fn implicit_type(&self) -> Option<LsonType> {
         self.implicit_type
     }
@@ -38,6 +44,7 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for TypedIdentifier {
     }
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
+        let start = queue.next_span();
         if let Some(LibrettoLogicToken::Identifier(ident)) = queue.pop() {
             let implicit_type = {
                 if queue.pop_if_next_is(LogicOrdinal::Colon).is_some() {
@@ -47,7 +54,7 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for TypedIdentifier {
                 }
             };
 
-            Some(TypedIdentifier { implicit_type, ident })
+            Some(TypedIdentifier { implicit_type, ident, span: start.to(queue.last_span()) })
         } else {
             None
         }
@@ -66,7 +73,7 @@ impl <'a> LibrettoParsable<'a, LibrettoLogicToken> for TypedIdentifier {
 pub struct KeyValuePair<'a, P, T>
 where
     P: LibrettoParsable<'a, T> + Sized,
-    T: Logos<'a> + PartialEq + Ordinal + Clone + Debug + 'a,
+    T: Logos<'a, Source = str> + PartialEq + Ordinal + Clone + Debug + 'a,
     T::Extras: Clone,
     Self: Sized,
 {
@@ -78,7 +85,7 @@ where
 impl <'a, P, T> KeyValuePair<'a, P, T>
 where
     P: LibrettoParsable<'a, T> + Sized,
-    T: Logos<'a> + PartialEq + Ordinal + Clone + Debug + 'a,
+    T: Logos<'a, Source = str> + PartialEq + Ordinal + Clone + Debug + 'a,
     T::Extras: Clone,
     Self: Sized,
 {
@@ -97,7 +104,7 @@ where
     Self: Sized,
 {
     fn raw_check(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>) -> bool {
-        if !queue.next_is(LogicOrdinal::Identifier) {
+        if !queue.next_is([LogicOrdinal::Identifier, LogicOrdinal::StringLiteral]) {
             return false;
         }
         if !queue.next_is(LogicOrdinal::Colon) {
@@ -111,14 +118,15 @@ where
 
     fn parse(queue: &mut LibrettoTokenQueue<'a, LibrettoLogicToken>, compile_time : &mut LibrettoCompiletime) -> Option<Self> {
         queue.reset();
-        let ident = queue.pop_if_next_is(LogicOrdinal::Identifier).unwrap();
+        let ident = queue.pop_if_next_is([LogicOrdinal::Identifier, LogicOrdinal::StringLiteral]).unwrap();
         queue.pop_if_next_is(LogicOrdinal::Colon);
         let value = parse_ast!(P, queue, compile_time);
 
-        if let LibrettoLogicToken::Identifier(key) = ident {
-            Some(KeyValuePair { key, value, _phantom: &PhantomData })
-        } else {
-            None
+        match ident {
+            LibrettoLogicToken::Identifier(key) | LibrettoLogicToken::StringLiteral(key) => {
+                Some(KeyValuePair { key, value, _phantom: &PhantomData })
+            },
+            _ => None,
         }
     }
 
@@ -134,7 +142,7 @@ where
 pub struct CommaSeparatedList<'a, P, T>
 where
     P: LibrettoParsable<'a, T> + Sized,
-    T: Logos<'a> + PartialEq + Ordinal + Clone + Debug + 'a,
+    T: Logos<'a, Source = str> + PartialEq + Ordinal + Clone + Debug + 'a,
     T::Extras: Clone,
     Self: Sized,
 {
@@ -145,7 +153,7 @@ where
 impl<'a, P, T> CommaSeparatedList<'a, P, T>
 where
     P: LibrettoParsable<'a, T> + Sized,
-    T: Logos<'a> + PartialEq + Ordinal + Clone + Debug + 'a,
+    T: Logos<'a, Source = str> + PartialEq + Ordinal + Clone + Debug + 'a,
     T::Extras: Clone,
     Self: Sized,
 {
@@ -157,7 +165,7 @@ where
 impl<'a, P, T> Debug for CommaSeparatedList<'a, P, T>
 where
     P: LibrettoParsable<'a, T> + Sized + Debug,
-    T: Logos<'a> + PartialEq + Ordinal + Clone + Debug + 'a,
+    T: Logos<'a, Source = str> + PartialEq + Ordinal + Clone + Debug + 'a,
     T::Extras: Clone,
     Self: Sized,
 {
@@ -279,6 +287,7 @@ pub mod tests {
         let ast = parse_expr::<TypedIdentifier>("some_bool : bool");
         assert_eq!(ast.ident, "some_bool".to_string());
         assert_eq!(ast.implicit_type, Some(LsonType::Bool));
+        assert_eq!(ast.span(), Span::new(0, 16, 1, 1));
     }
 
     #[test]
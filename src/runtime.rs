@@ -1,22 +1,43 @@
 mod event;
+pub mod dialog;
 pub mod function;
 
 use crate::lson::Lson;
+use crate::parse::{DialogTarget, Script};
 use crate::scope::LibrettoScope;
-use std::collections::HashMap;
+use crate::span::Span;
+use crate::speaker::{LibrettoSpeaker, LibrettoSpeakerRegistry};
+use std::collections::{HashMap, VecDeque};
 
-use self::{event::LibrettoEventListener};
+use serde::{Deserialize, Serialize};
+
+use self::{event::LibrettoEventListener, dialog::{DialogCursor, LibrettoLine, LibrettoStep}};
 
 pub struct LibrettoRuntime {
     current_scope: LibrettoScope<Lson>,
     event_listeners: Vec<Box<dyn LibrettoEventListener>>,
+    cursor: Option<DialogCursor>,
+    return_stack: Vec<DialogCursor>,
+    choices: Vec<Option<DialogTarget>>,
+    pauses: VecDeque<LibrettoStep>,
+    speakers: Option<LibrettoSpeakerRegistry>,
+    request_handler: Option<Box<LibrettoRequestHandler>>,
 }
 
+/// Gives the value of a `request` from the host, or `None` if the host doesn't have it.
+pub type LibrettoRequestHandler = dyn FnMut(&str) -> Option<Lson>;
+
 impl Default for LibrettoRuntime {
     fn default() -> Self {
         LibrettoRuntime {
             current_scope : LibrettoScope {data : HashMap::new(), parrent: None},
-            event_listeners : Vec::new()
+            event_listeners : Vec::new(),
+            cursor : None,
+            return_stack : Vec::new(),
+            choices : Vec::new(),
+            pauses : VecDeque::new(),
+            speakers : None,
+            request_handler : None
         }
     }
 }
@@ -26,10 +47,158 @@ impl LibrettoRuntime {
     pub fn with_data(data : impl Into<HashMap<String, Lson>>) -> Self {
         LibrettoRuntime {
             current_scope: LibrettoScope { data: data.into(), parrent: None },
-            event_listeners: Vec::new()
+            event_listeners: Vec::new(),
+            cursor: None,
+            return_stack: Vec::new(),
+            choices: Vec::new(),
+            pauses: VecDeque::new(),
+            speakers: None,
+            request_handler: None
+        }
+    }
+
+    pub fn set_speakers(&mut self, speakers : LibrettoSpeakerRegistry) {
+        self.speakers = Some(speakers);
+    }
+
+    pub fn set_request_handler(&mut self, handler : impl FnMut(&str) -> Option<Lson> + 'static) {
+        self.request_handler = Some(Box::new(handler));
+    }
+
+    /// Asks the host for the value of a request. Without a request handler, nothing is available.
+    pub fn request(&mut self, ident : &str) -> Option<Lson> {
+        self.request_handler.as_mut().and_then(|handler| handler(ident))
+    }
+
+    /// Looks up a speaker in the speaker registry. Without a registry, the id is used as the name.
+    pub fn resolve_speaker(&self, id : &str) -> Result<LibrettoSpeaker, LibrettoRuntimeError> {
+        match &self.speakers {
+            Some(speakers) => speakers.get(id).cloned().ok_or_else(|| LibrettoRuntimeErrorKind::SpeakerNotDefined(id.to_string()).into()),
+            None => Ok(LibrettoSpeaker::new(id, id)),
+        }
+    }
+
+    /// Starts a run of the script at the given dialog, or at a labelled line with `dialog.label`. The
+    /// preamble of the script is run once into the root scope before the first line is reached.
+    pub fn start(&mut self, script : &Script, dialog : &str) -> Result<(), LibrettoRuntimeError> {
+        let cursor = DialogTarget::from_path(dialog).find(script.dialogs(), None)
+            .map_err(|_| LibrettoRuntimeErrorKind::DialogNotDefined(dialog.to_string()))?;
+
+        while self.current_scope.parrent.is_some() {
+            self.pop_scope();
+        }
+
+        self.pauses.clear();
+        for block in script.preamble() {
+            block.evaluate(self)?;
+        }
+
+        self.choices.clear();
+        self.return_stack.clear();
+        self.cursor = Some(cursor);
+        Ok(())
+    }
+
+    /// Moves the run forward by one step. Pauses made by `yield` and `wait` are given right after the
+    /// line that made them, and stepping again after a pause resumes the dialog where it left off.
+    pub fn step(&mut self, script : &Script) -> Result<LibrettoStep, LibrettoRuntimeError> {
+        if let Some(pause) = self.take_pause() {
+            return Ok(pause);
+        }
+
+        Ok(match self.next_line(script)? {
+            Some(line) if line.responses.is_empty() => LibrettoStep::LineReady(line),
+            Some(line) => LibrettoStep::ChoicesReady(line),
+            None => LibrettoStep::Finished,
+        })
+    }
+
+    /// Queues up a pause, which is given the next time the runtime is stepped.
+    pub fn pause(&mut self, step : LibrettoStep) {
+        self.pauses.push_back(step);
+    }
+
+    pub(crate) fn take_pause(&mut self) -> Option<LibrettoStep> {
+        self.pauses.pop_front()
+    }
+
+    /// Runs the next line of the current dialog. Lines whose variants all fail their conditions are
+    /// skipped. A line without responses that has a divert moves the dialog to the divert's target
+    /// once it is presented. When a called dialog runs out of lines, the dialog returns to the line
+    /// after the call. Returns `None` once the dialog has run out of lines and there is nothing to
+    /// return to.
+    pub fn next_line(&mut self, script : &Script) -> Result<Option<LibrettoLine>, LibrettoRuntimeError> {
+        if !self.choices.is_empty() {
+            return Err(LibrettoRuntimeErrorKind::ChoicePending.into());
+        }
+
+        while let Some(cursor) = self.cursor {
+            let line = script.dialogs().get(cursor.dialog).and_then(|dialog| dialog.lines().get(cursor.line));
+            let line = match line {
+                Some(line) => line,
+                None => {
+                    self.cursor = self.return_stack.pop();
+                    continue;
+                }
+            };
+
+            self.cursor = Some(DialogCursor { line: cursor.line + 1, ..cursor });
+            if let Some(presented) = line.present(self)? {
+                match presented.divert {
+                    Some(divert) if presented.choices.is_empty() => self.jump(&divert)?,
+                    _ => self.choices = presented.choices,
+                }
+                return Ok(Some(presented.line));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Picks one of the responses of the last line, and moves to the dialog it leads to.
+    pub fn choose(&mut self, index : usize) -> Result<(), LibrettoRuntimeError> {
+        let target = self.choices.get(index).cloned().ok_or(LibrettoRuntimeErrorKind::InvalidChoice(index))?;
+        let target = target.ok_or(LibrettoRuntimeErrorKind::ResponseUnavailable(index))?;
+        self.choices.clear();
+        self.jump(&target)
+    }
+
+    /// Moves to the line a target was resolved to when the script was compiled. If the target is a
+    /// call, the line the dialog was going to run next is pushed onto the return stack.
+    fn jump(&mut self, target : &DialogTarget) -> Result<(), LibrettoRuntimeError> {
+        let cursor = target.resolved().ok_or_else(|| LibrettoRuntimeErrorKind::DialogNotDefined(target.to_string()).at(target.span()))?;
+        if let Some(current) = self.cursor.filter(|_| target.is_call()) {
+            self.return_stack.push(current);
+        }
+        self.cursor = Some(cursor);
+        Ok(())
+    }
+
+    /// Takes a snapshot of the run, so it can be picked back up later with `restore`.
+    pub fn save(&self) -> LibrettoSaveState {
+        let mut root = &self.current_scope;
+        while let Some(parrent) = &root.parrent {
+            root = parrent;
+        }
+
+        LibrettoSaveState {
+            cursor: self.cursor,
+            return_stack: self.return_stack.clone(),
+            choices: self.choices.clone(),
+            pauses: self.pauses.clone(),
+            data: root.data.clone(),
         }
     }
 
+    /// Picks a run back up from a snapshot taken with `save`. The preamble is not run again.
+    pub fn restore(&mut self, state : LibrettoSaveState) {
+        self.current_scope = LibrettoScope::new(state.data);
+        self.cursor = state.cursor;
+        self.return_stack = state.return_stack;
+        self.choices = state.choices;
+        self.pauses = state.pauses;
+    }
+
     pub fn get_data(&self, key : &str) -> Lson {
         self.current_scope.get_data(key)
     }
@@ -50,19 +219,106 @@ impl LibrettoRuntime {
         self.current_scope.data.insert(ident.to_string(), value);
     }
 
+    pub fn replace_data(&mut self, ident : &str, value : Lson) -> bool {
+        self.current_scope.replace_data(ident, value)
+    }
+
     pub fn has_data(&self, ident : &str) -> bool {
         self.current_scope.has_data(ident)
     }
 }
 
+/// A snapshot of a run: where the dialog is, the lines waiting to be returned to by calls, the
+/// responses waiting to be chosen, the pauses waiting to be given and the global variables.
+/// It can be serialized to keep it in a save file, as long as none of the variables hold a
+/// function.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LibrettoSaveState {
+    cursor: Option<DialogCursor>,
+    return_stack: Vec<DialogCursor>,
+    choices: Vec<Option<DialogTarget>>,
+    pauses: VecDeque<LibrettoStep>,
+    #[serde(with = "crate::lson::tagged::map")]
+    data: HashMap<String, Lson>,
+}
+
+impl LibrettoSaveState {
+    /// How many calls are waiting to be returned to.
+    pub fn call_depth(&self) -> usize {
+        self.return_stack.len()
+    }
+}
+
 pub trait LibrettoEvaluator {
     fn evaluate(&self, runtime: &mut LibrettoRuntime) -> LibrettoRuntimeResult;
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum LibrettoRuntimeError {
+/// An error that stopped a script from running. Errors that come from the script point to where in
+/// the script they happened, while errors caused by the host, like choosing a response that doesn't
+/// exist, have no span.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{kind}")]
+pub struct LibrettoRuntimeError {
+    pub kind: LibrettoRuntimeErrorKind,
+    pub span: Option<Span>,
+}
+
+impl LibrettoRuntimeErrorKind {
+    pub fn at(self, span: Span) -> LibrettoRuntimeError {
+        LibrettoRuntimeError { kind: self, span: Some(span) }
+    }
+}
+
+impl LibrettoRuntimeError {
+    /// Points the error at the given span, unless it already points somewhere.
+    pub fn or_at(self, span: Span) -> Self {
+        LibrettoRuntimeError { span: self.span.or(Some(span)), ..self }
+    }
+}
+
+impl From<LibrettoRuntimeErrorKind> for LibrettoRuntimeError {
+    fn from(kind: LibrettoRuntimeErrorKind) -> Self {
+        LibrettoRuntimeError { kind, span: None }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum LibrettoRuntimeErrorKind {
     #[error("There is no assignable variable with the identifier '{0}'")]
     VariableNotDefined(String),
+
+    #[error("There is no dialog or label named '{0}'")]
+    DialogNotDefined(String),
+
+    #[error("A condition must evaluate to a bool, but it evaluated to {0}")]
+    ConditionNotBool(String),
+
+    #[error("The current line is waiting for a response to be chosen")]
+    ChoicePending,
+
+    #[error("There is no response with the index {0}")]
+    InvalidChoice(usize),
+
+    #[error("The response with the index {0} is not available")]
+    ResponseUnavailable(usize),
+
+    #[error("A speaker must evaluate to a string, but it evaluated to {0}")]
+    SpeakerNotString(String),
+
+    #[error("The speaker '{0}' is not in the speaker registry")]
+    SpeakerNotDefined(String),
+
+    #[error("A wait must be given a number of seconds, but it was given {0}")]
+    WaitNotNumber(String),
+
+    #[error("The host has no value for the request '{0}', and the request has no default")]
+    RequestNotAvailable(String),
+
+    #[error("The request '{0}' is of type {1}, but the host gave a value of type {2}")]
+    RequestTypeMismatch(String, String, String),
+
+    #[error("A value of type {0} can't be ordered against a value of type {1}")]
+    Incomparable(String, String),
 }
 
 pub type LibrettoRuntimeResult = Result<Lson, LibrettoRuntimeError>;
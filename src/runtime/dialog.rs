@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use crate::{lson::Lson, speaker::LibrettoSpeaker};
+
+/// A line of dialog that is ready to be shown to the player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibrettoLine {
+    pub speaker: Option<LibrettoSpeaker>,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub responses: Vec<LibrettoResponse>,
+}
+
+/// A response that can be picked for a line. Responses whose guard failed without giving a reason are
+/// left out of the line, while ones that gave a reason are shown as unavailable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibrettoResponse {
+    pub text: String,
+    pub available: bool,
+    pub reason: Option<String>,
+}
+
+/// What happened when the runtime was stepped. A line with responses is given as `ChoicesReady`, and
+/// one of its responses has to be chosen before the runtime can be stepped past it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LibrettoStep {
+    LineReady(LibrettoLine),
+    ChoicesReady(LibrettoLine),
+    Yielded(#[serde(with = "crate::lson::tagged")] Lson),
+    Waiting(f64),
+    Finished,
+}
+
+/// Where the runtime currently is inside of a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DialogCursor {
+    pub dialog: usize,
+    pub line: usize,
+}
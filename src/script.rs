@@ -0,0 +1,190 @@
+use std::{ops::Deref, sync::Arc};
+
+use bincode::Options;
+
+use crate::{diagnostic::Diagnostics, parse::Script};
+
+/// The bytes every packaged script starts with.
+const MAGIC: &[u8; 4] = b"LBRT";
+
+/// The version of the packaged format, written right after the magic bytes. It goes up whenever
+/// the shape of the AST changes, since a package from another version can't be read safely.
+pub const FORMAT_VERSION: u16 = 1;
+
+//==================================================================================================
+//          Libretto Script
+//==================================================================================================
+
+/// A script that has been compiled without errors. It can't be changed once it is built, and
+/// cloning it only shares the same script, so one compile can be handed to as many runtimes as
+/// needed, on as many threads as needed. Each runtime keeps its own place and variables.
+#[derive(Clone)]
+pub struct LibrettoScript {
+    script: Arc<Script>,
+    warnings: Arc<Diagnostics>,
+}
+
+impl LibrettoScript {
+    pub(crate) fn new(script: Script, warnings: Diagnostics) -> Self {
+        LibrettoScript { script: Arc::new(script), warnings: Arc::new(warnings) }
+    }
+
+    /// The warnings found while compiling the script.
+    pub fn warnings(&self) -> &Diagnostics {
+        &self.warnings
+    }
+
+    pub fn dialog_names(&self) -> impl Iterator<Item = &str> {
+        self.script.dialogs().iter().map(|dialog| dialog.name())
+    }
+
+    /// Packages the script into a compact binary form that can be shipped instead of the source. The
+    /// package holds the checked AST, so loading it skips lexing and validation. Warnings are not
+    /// part of the package.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, LibrettoPackageError> {
+        let mut bytes = Vec::from(&MAGIC[..]);
+        bytes.extend(FORMAT_VERSION.to_le_bytes());
+        bincode::DefaultOptions::new()
+            .serialize_into(&mut bytes, &*self.script)
+            .map_err(|error| LibrettoPackageError::Encode(error.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Loads a script that was packaged with [`LibrettoScript::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LibrettoPackageError> {
+        let payload = bytes.strip_prefix(&MAGIC[..]).ok_or(LibrettoPackageError::NotAScript)?;
+        if payload.len() < 2 {
+            return Err(LibrettoPackageError::NotAScript);
+        }
+        let version = u16::from_le_bytes([payload[0], payload[1]]);
+        if version != FORMAT_VERSION {
+            return Err(LibrettoPackageError::UnsupportedVersion(version, FORMAT_VERSION));
+        }
+
+        let script = bincode::DefaultOptions::new()
+            .deserialize(&payload[2..])
+            .map_err(|error| LibrettoPackageError::Decode(error.to_string()))?;
+        Ok(LibrettoScript::new(script, Diagnostics::default()))
+    }
+}
+
+impl Deref for LibrettoScript {
+    type Target = Script;
+
+    fn deref(&self) -> &Self::Target {
+        &self.script
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LibrettoPackageError {
+    #[error("The data is not a packaged libretto script.")]
+    NotAScript,
+
+    #[error("The script was packaged with format version {0}, but only version {1} can be loaded.")]
+    UnsupportedVersion(u16, u16),
+
+    #[error("The script could not be packaged: {0}")]
+    Encode(String),
+
+    #[error("The packaged script is damaged: {0}")]
+    Decode(String),
+}
+
+//==================================================================================================
+//          Libretto Script Tests
+//==================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{
+        compiler::{compile, LibrettoCompileErrorKind, LibrettoCompiletime, LibrettoLint, LibrettoLintConfig},
+        lson::{Lson, LsonType},
+        runtime::LibrettoRuntime,
+    };
+
+    use super::*;
+
+    const SCRIPT: &str = r#"
+        <let visits = 0;>
+        start {
+            | <visits = visits + 1> "Hello there." -- shop
+        }
+        shop {
+            | "Welcome to the shop."
+        }
+    "#;
+
+    #[test]
+    fn compile_script() {
+        let script = compile(SCRIPT).unwrap();
+        assert!(script.warnings().is_empty());
+        assert_eq!(script.dialog_names().collect::<Vec<_>>(), ["start", "shop"]);
+
+        let mut runtime = LibrettoRuntime::default();
+        runtime.start(&script, "start").unwrap();
+        assert_eq!(runtime.next_line(&script).unwrap().unwrap().text, "Hello there.");
+        assert_eq!(runtime.next_line(&script).unwrap().unwrap().text, "Welcome to the shop.");
+        assert_eq!(runtime.get_data("visits"), Lson::Int(1));
+
+        let diagnostics = compile("start { | <if 3> \"Hello.\" -- nowhere }").err().unwrap();
+        assert_eq!(diagnostics.errors().count(), 2);
+        assert_eq!(diagnostics.iter().next().unwrap().code, LibrettoCompileErrorKind::ConditionNotBool(String::new()).code());
+        assert!(diagnostics.render("start.lib", "").contains("error[E0014]"));
+
+        let diagnostics = compile("start { | <let unused = 1;> \"Hello.\" }").unwrap().warnings().clone();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics.has_errors());
+
+        let mut compile_time = LibrettoCompiletime::with_data([(String::from("gold"), LsonType::Int)]);
+        compile_time.set_lints(LibrettoLintConfig::default().deny(LibrettoLint::UnusedVariable));
+        assert!(compile_time.compile("start { | <let unused = gold;> \"Hello.\" }").is_err());
+    }
+
+    #[test]
+    fn package_script() {
+        let script = compile(SCRIPT).unwrap();
+        let bytes = script.to_bytes().unwrap();
+        assert_eq!(&bytes[..6], b"LBRT\x01\x00");
+        assert_eq!(script.to_bytes().unwrap(), bytes);
+
+        let loaded = LibrettoScript::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.dialog_names().collect::<Vec<_>>(), ["start", "shop"]);
+        let mut runtime = LibrettoRuntime::default();
+        runtime.start(&loaded, "start").unwrap();
+        assert_eq!(runtime.next_line(&loaded).unwrap().unwrap().text, "Hello there.");
+        assert_eq!(runtime.next_line(&loaded).unwrap().unwrap().text, "Welcome to the shop.");
+        assert_eq!(runtime.get_data("visits"), Lson::Int(1));
+
+        assert_eq!(LibrettoScript::from_bytes(b"start { }").err(), Some(LibrettoPackageError::NotAScript));
+        assert_eq!(LibrettoScript::from_bytes(b"LBRT").err(), Some(LibrettoPackageError::NotAScript));
+
+        let mut newer = bytes.clone();
+        newer[4] = 9;
+        assert_eq!(LibrettoScript::from_bytes(&newer).err(), Some(LibrettoPackageError::UnsupportedVersion(9, FORMAT_VERSION)));
+        assert!(matches!(LibrettoScript::from_bytes(&bytes[..bytes.len() - 3]), Err(LibrettoPackageError::Decode(_))));
+    }
+
+    #[test]
+    fn share_script_between_threads() {
+        let script = compile(SCRIPT).unwrap();
+        let handles: Vec<_> = (0..4).map(|_| {
+            let script = script.clone();
+            thread::spawn(move || {
+                let mut runtime = LibrettoRuntime::default();
+                runtime.start(&script, "start").unwrap();
+                let mut lines = Vec::new();
+                while let Some(line) = runtime.next_line(&script).unwrap() {
+                    lines.push(line.text);
+                }
+                lines
+            })
+        }).collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), ["Hello there.", "Welcome to the shop."]);
+        }
+    }
+}
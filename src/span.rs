@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Where something came from in a script. `start` and `end` are byte offsets into the whole source,
+/// while `line` and `column` point at the start and count from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span { start, end, line, column }
+    }
+
+    /// Finds the line and column of a byte range in the given source.
+    pub fn locate(source: &str, start: usize, end: usize) -> Self {
+        SourceCursor::default().locate(source, start, end)
+    }
+
+    /// A span that starts where this one does, and ends where the other one does.
+    pub fn to(self, other: Span) -> Span {
+        Span { end: other.end.max(self.start), ..self }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Remembers the line and column of the last offset it was asked about, so that walking forward
+/// through a source doesn't count every line from the start again.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SourceCursor {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Default for SourceCursor {
+    fn default() -> Self {
+        SourceCursor { offset: 0, line: 1, column: 1 }
+    }
+}
+
+impl SourceCursor {
+    pub fn locate(&mut self, source: &str, start: usize, end: usize) -> Span {
+        if start < self.offset {
+            *self = SourceCursor::default();
+        }
+
+        for character in source[self.offset..start].chars() {
+            if character == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.offset = start;
+
+        Span::new(start, end, self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_span() {
+        let source = "start {\n  | \"Hello.\"\n}";
+        assert_eq!(Span::locate(source, 0, 5), Span::new(0, 5, 1, 1));
+        assert_eq!(Span::locate(source, 10, 11), Span::new(10, 11, 2, 3));
+
+        let mut cursor = SourceCursor::default();
+        assert_eq!(cursor.locate(source, 12, 20), Span::new(12, 20, 2, 5));
+        assert_eq!(cursor.locate(source, 21, 22), Span::new(21, 22, 3, 1));
+        assert_eq!(cursor.locate(source, 0, 5), Span::new(0, 5, 1, 1));
+    }
+
+    #[test]
+    fn merge_span() {
+        let span = Span::new(2, 4, 1, 3).to(Span::new(8, 10, 2, 1));
+        assert_eq!(span, Span::new(2, 10, 1, 3));
+        assert_eq!(span.len(), 8);
+    }
+}
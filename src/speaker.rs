@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A character that can speak a line. The id is what is written after the `:` in a script, and the
+/// rest is what the host needs to show the line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibrettoSpeaker {
+    pub id: String,
+    pub name: String,
+    pub portrait: Option<String>,
+    pub voice: Option<String>,
+}
+
+impl LibrettoSpeaker {
+    pub fn new(id: &str, name: &str) -> Self {
+        LibrettoSpeaker {
+            id: id.to_string(),
+            name: name.to_string(),
+            portrait: None,
+            voice: None,
+        }
+    }
+
+    pub fn with_portrait(mut self, portrait: &str) -> Self {
+        self.portrait = Some(portrait.to_string());
+        self
+    }
+
+    pub fn with_voice(mut self, voice: &str) -> Self {
+        self.voice = Some(voice.to_string());
+        self
+    }
+}
+
+/// The speakers that the host knows about, by id. When a registry is given, speakers written in a
+/// script are checked against it at compile time, and resolved through it at runtime.
+#[derive(Debug, Clone, Default)]
+pub struct LibrettoSpeakerRegistry {
+    speakers: HashMap<String, LibrettoSpeaker>,
+}
+
+impl LibrettoSpeakerRegistry {
+    pub fn register(&mut self, speaker: LibrettoSpeaker) {
+        self.speakers.insert(speaker.id.clone(), speaker);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&LibrettoSpeaker> {
+        self.speakers.get(id)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.speakers.contains_key(id)
+    }
+}
+
+impl<const COUNT: usize> From<[LibrettoSpeaker; COUNT]> for LibrettoSpeakerRegistry {
+    fn from(value: [LibrettoSpeaker; COUNT]) -> Self {
+        let mut registry = LibrettoSpeakerRegistry::default();
+        for speaker in value {
+            registry.register(speaker);
+        }
+        registry
+    }
+}
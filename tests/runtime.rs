@@ -0,0 +1,70 @@
+use libretto::{
+    LibrettoCompiletime, LibrettoLint, LibrettoLintConfig, LibrettoRuntime, LibrettoRuntimeErrorKind, LibrettoSpeaker,
+    LibrettoSpeakerRegistry, LibrettoStep, LintLevel, Lson, LsonType, Span,
+};
+
+const SCRIPT: &str = r#"
+    <let visits = 0;>
+    start {
+        | :jake <visits = visits + 1> "Welcome in." #happy
+        | :jake "What will it be?" -> <if gold != 0> "A sword." sword -> "Nothing." leave
+    }
+    sword {
+        | :jake "A fine choice."
+    }
+    leave {
+        | :jake "Come again."
+    }
+"#;
+
+fn speakers() -> LibrettoSpeakerRegistry {
+    LibrettoSpeakerRegistry::from([LibrettoSpeaker::new("jake", "Jake").with_portrait("jake.png")])
+}
+
+fn compile() -> libretto::LibrettoScript {
+    let mut compile_time = LibrettoCompiletime::with_data([(String::from("gold"), LsonType::Int)]);
+    compile_time.set_speakers(speakers());
+    compile_time.set_lints(LibrettoLintConfig::default().with_default(LintLevel::Deny).allow(LibrettoLint::UnusedVariable));
+    compile_time.compile(SCRIPT).unwrap()
+}
+
+#[test]
+fn compile_start_step_choose() {
+    let script = compile();
+    let mut runtime = LibrettoRuntime::with_data([(String::from("gold"), Lson::Int(12))]);
+    runtime.set_speakers(speakers());
+    runtime.start(&script, "start").unwrap();
+
+    let LibrettoStep::LineReady(line) = runtime.step(&script).unwrap() else { panic!("expected a line") };
+    assert_eq!(line.speaker.unwrap().portrait.as_deref(), Some("jake.png"));
+    assert_eq!(line.text, "Welcome in.");
+    assert_eq!(line.tags, ["happy"]);
+
+    let LibrettoStep::ChoicesReady(line) = runtime.step(&script).unwrap() else { panic!("expected choices") };
+    let responses: Vec<&str> = line.responses.iter().map(|response| response.text.as_str()).collect();
+    assert_eq!(responses, ["A sword.", "Nothing."]);
+    assert_eq!(runtime.step(&script).unwrap_err().kind, LibrettoRuntimeErrorKind::ChoicePending);
+
+    let save = runtime.save();
+    runtime.choose(0).unwrap();
+    let LibrettoStep::LineReady(line) = runtime.step(&script).unwrap() else { panic!("expected a line") };
+    assert_eq!(line.text, "A fine choice.");
+    assert_eq!(runtime.step(&script).unwrap(), LibrettoStep::Finished);
+
+    runtime.restore(save);
+    runtime.choose(1).unwrap();
+    let LibrettoStep::LineReady(line) = runtime.step(&script).unwrap() else { panic!("expected a line") };
+    assert_eq!(line.text, "Come again.");
+    assert_eq!(runtime.get_data("visits"), Lson::Int(1));
+}
+
+#[test]
+fn compile_errors_point_into_the_source() {
+    let diagnostics = LibrettoCompiletime::default().compile("start { | <if 3> \"Hello.\" }").err().unwrap();
+    let error = diagnostics.errors().next().unwrap();
+    assert_eq!(error.span, Span::new(14, 15, 1, 15));
+
+    let mut runtime = LibrettoRuntime::default();
+    let error = runtime.start(&compile(), "nowhere").unwrap_err();
+    assert_eq!(error.kind, LibrettoRuntimeErrorKind::DialogNotDefined("nowhere".to_string()));
+}